@@ -0,0 +1,518 @@
+//! This module is optional and can be enabled using the `archive` Cargo feature.
+//!
+//! Compressed archives of identical trees often differ byte for byte, e.g. because of different compression levels,
+//! entry order or timestamps. The functions of this module hash an archive by its logical content instead: the
+//! digest equals the digest of the directory the archive is extracted into, calculated by [`chksum`](crate::chksum)
+//! or, with the same options, by [`chksum_with`](crate::chksum_with) including the
+//! [metadata](crate::options#metadata) of the entries.
+//!
+//! Supported formats are tar, gzip or zstd compressed tar and zip, see [`Format`].
+//!
+//! # Extraction Rules
+//!
+//! The archive is interpreted as an extraction would do it:
+//!
+//! * directories missing from the archive but containing entries are created with `0o755` permission bits,
+//! * a later entry of the same path replaces an earlier one,
+//! * hard links have the content and metadata of their target,
+//! * symbolic links are followed within the archive unless the targets are
+//!   [included](crate::ChksumOptions::include_symlink_targets),
+//! * device files and named pipes are skipped,
+//! * zip entries without Unix permission bits have `0o644` for files and `0o755` for directories.
+//!
+//! Entries with absolute paths or paths escaping the archive with `..`, and symbolic links pointing outside the
+//! archive, to missing entries or to their own ancestor directories are rejected with an error.
+//!
+//! Files are hashed in the order of directory hashing, which generally differs from the order of entries in the
+//! archive. Their contents are therefore spooled to a temporary file in the [temporary
+//! directory](std::env::temp_dir) while the archive is read, so memory usage doesn't grow with the size of the
+//! archive. The same holds for zip archives read by [`chksum_reader`], which need random access.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `archive` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["archive"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features archive
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256 as sha2_256;
+//! use chksum_sha2_256::archive;
+//!
+//! # fn wrapper(tarball: &Path, extracted: &Path) -> Result<()> {
+//! let digest = archive::chksum(tarball)?;
+//! assert_eq!(digest, sha2_256::chksum(extracted)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use flate2::read::MultiGzDecoder;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::{temporary, ChksumOptions, Digest, Result, SHA2_256};
+
+/// The maximum number of symbolic links followed while resolving a path.
+const MAX_SYMLINKS: usize = 40;
+
+/// The default permission bits of directories.
+const DIRECTORY_MODE: u32 = 0o755;
+
+/// The default permission bits of files.
+const FILE_MODE: u32 = 0o644;
+
+/// An archive format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// An uncompressed tar archive.
+    Tar,
+    /// A gzip compressed tar archive.
+    TarGzip,
+    /// A zstd compressed tar archive.
+    TarZstd,
+    /// A zip archive.
+    Zip,
+}
+
+impl Format {
+    /// Detects the format from the first bytes of the archive.
+    ///
+    /// Data which doesn't start with a gzip, zstd or zip signature is assumed to be an uncompressed tar archive.
+    #[must_use]
+    pub fn detect(header: &[u8]) -> Self {
+        if header.starts_with(&[0x1F, 0x8B]) {
+            Self::TarGzip
+        } else if header.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Self::TarZstd
+        } else if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") {
+            Self::Zip
+        } else {
+            Self::Tar
+        }
+    }
+}
+
+/// Computes the hash of the archive at the given path by its logical content.
+///
+/// The format is detected from the content of the file, see [`Format::detect`].
+///
+/// # Errors
+///
+/// Returns an error when the archive cannot be read or is invalid, see the [module documentation](self).
+pub fn chksum(path: impl AsRef<Path>) -> Result<Digest> {
+    chksum_with(path, &mut ChksumOptions::new())
+}
+
+/// Computes the hash of the archive at the given path by its logical content using the provided options.
+///
+/// # Errors
+///
+/// Returns an error when the archive cannot be read or is invalid, see the [module documentation](self).
+pub fn chksum_with(path: impl AsRef<Path>, options: &mut ChksumOptions) -> Result<Digest> {
+    let mut file = File::open(path)?;
+    let mut header = Vec::with_capacity(4);
+    file.by_ref().take(4).read_to_end(&mut header)?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut spool = Spool::new()?;
+    let tree = match Format::detect(&header) {
+        Format::Zip => read_zip(file, &mut spool)?,
+        format => read_tar(file, format, &mut spool)?,
+    };
+    tree.digest(&mut spool, options)
+}
+
+/// Computes the hash of the archive read from the given reader by its logical content.
+///
+/// # Errors
+///
+/// Returns an error when the archive cannot be read or is invalid, see the [module documentation](self).
+pub fn chksum_reader(reader: impl Read, format: Format, options: &mut ChksumOptions) -> Result<Digest> {
+    let mut spool = Spool::new()?;
+    let tree = match format {
+        Format::Zip => {
+            let mut archive = Spool::new()?;
+            archive.append(reader)?;
+            read_zip(&mut archive.file, &mut spool)?
+        },
+        format => read_tar(reader, format, &mut spool)?,
+    };
+    tree.digest(&mut spool, options)
+}
+
+/// A temporary file holding data of the archive until it is hashed.
+///
+/// The file is removed when the spool is dropped.
+#[derive(Debug)]
+struct Spool {
+    file: File,
+    path: PathBuf,
+    length: u64,
+}
+
+impl Spool {
+    fn new() -> io::Result<Self> {
+        let (file, path) = temporary::create(&env::temp_dir().join("chksum-sha2-256-archive"))?;
+        Ok(Self { file, path, length: 0 })
+    }
+
+    /// Appends all data of the reader, returning its offset and length in the spool.
+    fn append(&mut self, mut reader: impl Read) -> io::Result<(u64, u64)> {
+        let offset = self.length;
+        self.file.seek(SeekFrom::Start(offset))?;
+        let length = io::copy(&mut reader, &mut self.file)?;
+        self.length += length;
+        Ok((offset, length))
+    }
+
+    /// Returns a reader of the data at the given offset.
+    fn read(&mut self, offset: u64, length: u64) -> io::Result<io::Take<&mut File>> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        Ok((&mut self.file).take(length))
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// An entry of the extracted tree.
+#[derive(Debug)]
+enum Node {
+    /// A file with its content stored in the [`Spool`].
+    File {
+        mode: u32,
+        offset: u64,
+        size: u64,
+    },
+    Directory {
+        mode: u32,
+        children: BTreeMap<Vec<u8>, Node>,
+    },
+    Symlink {
+        mode: u32,
+        target: Vec<u8>,
+    },
+    HardLink {
+        target: Vec<Vec<u8>>,
+    },
+}
+
+/// The tree of entries of an archive.
+#[derive(Debug)]
+struct Tree {
+    root: Node,
+}
+
+impl Tree {
+    fn new() -> Self {
+        let root = Node::Directory {
+            mode: DIRECTORY_MODE,
+            children: BTreeMap::new(),
+        };
+        Self { root }
+    }
+
+    /// Inserts the node at the given path, creating missing parent directories.
+    fn insert(&mut self, path: &[u8], node: Node) -> io::Result<()> {
+        let components = components(path)?;
+        let Some((name, parents)) = components.split_last() else {
+            // the root directory itself, e.g. `./`
+            if let (Node::Directory { mode, .. }, Node::Directory { mode: new, .. }) = (&mut self.root, node) {
+                *mode = new;
+            }
+            return Ok(());
+        };
+        let mut current = &mut self.root;
+        for parent in parents {
+            let Node::Directory { children, .. } = current else {
+                return Err(invalid_path(path, "parent isn't a directory"));
+            };
+            current = children.entry(parent.clone()).or_insert_with(|| {
+                Node::Directory {
+                    mode: DIRECTORY_MODE,
+                    children: BTreeMap::new(),
+                }
+            });
+        }
+        let Node::Directory { children, .. } = current else {
+            return Err(invalid_path(path, "parent isn't a directory"));
+        };
+        match (children.get_mut(name), node) {
+            // an existing directory keeps its entries
+            (Some(Node::Directory { mode, .. }), Node::Directory { mode: new, .. }) => *mode = new,
+            (_, node) => {
+                children.insert(name.clone(), node);
+            },
+        }
+        Ok(())
+    }
+
+    /// Computes the digest of the tree, reading the contents of files from the spool.
+    fn digest(&self, spool: &mut Spool, options: &mut ChksumOptions) -> Result<Digest> {
+        let Node::Directory { children, .. } = &self.root else {
+            unreachable!("the root is always a directory");
+        };
+        let mut walk = Walk {
+            tree: self,
+            spool,
+            options,
+            hash: SHA2_256::new(),
+            directories: vec![Vec::new()],
+        };
+        walk.hash_children(&[], children, 0)?;
+        Ok(walk.hash.digest())
+    }
+
+    /// Resolves the link at the given path to the path and node of its final target.
+    fn resolve(&self, mut path: Vec<Vec<u8>>, mut symlinks: usize) -> io::Result<(Vec<Vec<u8>>, &Node)> {
+        loop {
+            if symlinks > MAX_SYMLINKS {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "too many levels of symbolic links",
+                ));
+            }
+            let (resolved, node) = self.lookup(&path, &mut symlinks)?;
+            path = match node {
+                Node::Symlink { target, .. } => {
+                    let mut parent = resolved;
+                    parent.pop();
+                    join(parent, target)?
+                },
+                Node::HardLink { target } => target.clone(),
+                node => return Ok((resolved, node)),
+            };
+            symlinks += 1;
+        }
+    }
+
+    /// Looks up the node at the given path, following symbolic links of parent directories.
+    fn lookup(&self, path: &[Vec<u8>], symlinks: &mut usize) -> io::Result<(Vec<Vec<u8>>, &Node)> {
+        let mut resolved = Vec::new();
+        let mut current = &self.root;
+        for (index, name) in path.iter().enumerate() {
+            let children = loop {
+                match current {
+                    Node::Directory { children, .. } => break children,
+                    Node::Symlink { .. } | Node::HardLink { .. } => {
+                        *symlinks += 1;
+                        if *symlinks > MAX_SYMLINKS {
+                            let message = "too many levels of symbolic links";
+                            return Err(io::Error::new(ErrorKind::InvalidData, message));
+                        }
+                        let (path, node) = self.resolve(resolved.clone(), *symlinks)?;
+                        resolved = path;
+                        current = node;
+                    },
+                    Node::File { .. } => return Err(not_found(&path[..index])),
+                }
+            };
+            current = children.get(name).ok_or_else(|| not_found(&path[..=index]))?;
+            resolved.push(name.clone());
+        }
+        Ok((resolved, current))
+    }
+}
+
+/// The state of hashing a tree in the order of directory hashing.
+struct Walk<'a> {
+    tree: &'a Tree,
+    spool: &'a mut Spool,
+    options: &'a mut ChksumOptions,
+    hash: SHA2_256,
+    /// The paths of the directories being hashed, from the root to the current one.
+    directories: Vec<Vec<Vec<u8>>>,
+}
+
+impl<'a> Walk<'a> {
+    /// Hashes the entries of a directory in the order of directory hashing.
+    fn hash_children(
+        &mut self,
+        path: &[Vec<u8>],
+        children: &'a BTreeMap<Vec<u8>, Node>,
+        symlinks: usize,
+    ) -> io::Result<()> {
+        for (name, node) in children {
+            let mut path = path.to_vec();
+            path.push(name.clone());
+            self.hash_node(path, node, symlinks)?;
+        }
+        Ok(())
+    }
+
+    /// Hashes an entry preceded by its metadata record, following links.
+    fn hash_node(&mut self, path: Vec<Vec<u8>>, node: &'a Node, symlinks: usize) -> io::Result<()> {
+        match node {
+            Node::File { mode, offset, size } => {
+                if self.options.includes_metadata() {
+                    self.hash.update(self.options.file_record(*mode, *size));
+                }
+                let content = self.spool.read(*offset, *size)?;
+                if self.options.update(content, &mut self.hash)? != *size {
+                    return Err(io::Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "spooled file content is truncated",
+                    ));
+                }
+                Ok(())
+            },
+            Node::Directory { mode, children } => {
+                if self.options.includes_metadata() {
+                    self.hash.update(self.options.directory_record(*mode));
+                }
+                self.directories.push(path.clone());
+                self.hash_children(&path, children, symlinks)?;
+                self.directories.pop();
+                Ok(())
+            },
+            Node::Symlink { mode, target } if self.options.includes_symlink_targets() => {
+                self.hash.update(self.options.symlink_record(*mode, target));
+                Ok(())
+            },
+            Node::Symlink { .. } | Node::HardLink { .. } => {
+                let (target, node) = self.tree.resolve(path.clone(), symlinks)?;
+                // following a link to a directory being hashed would never end
+                if matches!(node, Node::Directory { .. }) && self.directories.contains(&target) {
+                    return Err(invalid_path(&path.join(&b'/'), "link creates a cycle"));
+                }
+                self.hash_node(target, node, symlinks + 1)
+            },
+        }
+    }
+}
+
+/// Reads the tree of a tar archive.
+fn read_tar(reader: impl Read, format: Format, spool: &mut Spool) -> Result<Tree> {
+    match format {
+        Format::TarGzip => read_tar_entries(MultiGzDecoder::new(reader), spool),
+        Format::TarZstd => read_tar_entries(zstd::Decoder::new(reader)?, spool),
+        _ => read_tar_entries(reader, spool),
+    }
+}
+
+/// Reads the entries of an uncompressed tar archive.
+fn read_tar_entries(reader: impl Read, spool: &mut Spool) -> Result<Tree> {
+    let mut tree = Tree::new();
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let header = entry.header();
+        let kind = header.entry_type();
+        let mode = header.mode()? & 0o7777;
+        let path = entry.path_bytes().into_owned();
+        let node = if kind.is_dir() {
+            let children = BTreeMap::new();
+            Node::Directory { mode, children }
+        } else if kind.is_symlink() {
+            let target = entry.link_name_bytes().unwrap_or_default().into_owned();
+            Node::Symlink { mode, target }
+        } else if kind.is_hard_link() {
+            let target = entry.link_name_bytes().unwrap_or_default();
+            let target = components(&target)?;
+            Node::HardLink { target }
+        } else if kind.is_file() || kind.is_contiguous() || kind.is_gnu_sparse() {
+            let (offset, size) = spool.append(&mut entry)?;
+            Node::File { mode, offset, size }
+        } else {
+            continue;
+        };
+        tree.insert(&path, node)?;
+    }
+    Ok(tree)
+}
+
+/// Reads the tree of a zip archive.
+fn read_zip(reader: impl Read + Seek, spool: &mut Spool) -> Result<Tree> {
+    let mut tree = Tree::new();
+    let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(zip_error)?;
+        let path = file.name_raw().to_vec();
+        let node = if file.is_dir() {
+            let mode = file.unix_mode().map_or(DIRECTORY_MODE, |mode| mode & 0o7777);
+            let children = BTreeMap::new();
+            Node::Directory { mode, children }
+        } else if file.is_symlink() {
+            let mode = file.unix_mode().map_or(0o777, |mode| mode & 0o7777);
+            let mut target = Vec::new();
+            file.read_to_end(&mut target)?;
+            Node::Symlink { mode, target }
+        } else {
+            let mode = file.unix_mode().map_or(FILE_MODE, |mode| mode & 0o7777);
+            let (offset, size) = spool.append(&mut file)?;
+            Node::File { mode, offset, size }
+        };
+        tree.insert(&path, node)?;
+    }
+    Ok(tree)
+}
+
+/// Splits a relative archive path into its components.
+fn components(path: &[u8]) -> io::Result<Vec<Vec<u8>>> {
+    if path.starts_with(b"/") {
+        return Err(invalid_path(path, "absolute path"));
+    }
+    join(Vec::new(), path)
+}
+
+/// Joins the relative path to the base components, resolving `.` and `..` components.
+fn join(mut base: Vec<Vec<u8>>, path: &[u8]) -> io::Result<Vec<Vec<u8>>> {
+    if path.starts_with(b"/") {
+        return Err(invalid_path(path, "link points outside the archive"));
+    }
+    for component in path.split(|byte| *byte == b'/') {
+        match component {
+            b"" | b"." => {},
+            b".." => {
+                if base.pop().is_none() {
+                    return Err(invalid_path(path, "path escapes the archive"));
+                }
+            },
+            component => base.push(component.to_vec()),
+        }
+    }
+    Ok(base)
+}
+
+/// Returns an error describing an invalid path.
+fn invalid_path(path: &[u8], reason: &str) -> io::Error {
+    let message = format!("invalid path `{}`: {reason}", String::from_utf8_lossy(path));
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Returns an error describing a missing link target.
+fn not_found(path: &[Vec<u8>]) -> io::Error {
+    let message = format!(
+        "link target `{}` not found in the archive",
+        String::from_utf8_lossy(&path.join(&b'/'))
+    );
+    io::Error::new(ErrorKind::NotFound, message)
+}
+
+/// Converts a zip error to an I/O error.
+fn zip_error(error: ZipError) -> io::Error {
+    match error {
+        ZipError::Io(error) => error,
+        error => io::Error::new(ErrorKind::InvalidData, error),
+    }
+}
@@ -0,0 +1,250 @@
+//! This module is optional and can be enabled using the `hw-accel` Cargo feature.
+//!
+//! The [`SHA2_256`](crate::SHA2_256) hash uses a portable implementation of the SHA-2 256 compression function by
+//! default. With the `hw-accel` feature, the compression function is selected at runtime based on the CPU features:
+//!
+//! * [`Backend::ShaNi`] on `x86` and `x86_64` CPUs with the SHA extensions,
+//! * [`Backend::ArmV8`] on `aarch64` CPUs with the ARMv8 cryptography extensions,
+//! * [`Backend::Portable`] everywhere else.
+//!
+//! The backend is detected once and captured by each hash when it is created. All backends produce identical
+//! digests. Use [`SHA2_256::with_backend`](crate::SHA2_256::with_backend) to choose the backend of a single hash, e.g.
+//! to compare results in tests, or [`force_portable`] to disable acceleration for the whole process.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `hw-accel` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["hw-accel"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features hw-accel
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::backend::{self, Backend};
+//!
+//! println!("active backend: {}", backend::active());
+//!
+//! backend::force_portable(true);
+//! assert_eq!(backend::active(), Backend::Portable);
+//! backend::force_portable(false);
+//! ```
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+mod portable;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86;
+
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::constants::H;
+use crate::hash;
+
+/// The length of a single block in bytes.
+const BLOCK_LENGTH_BYTES: usize = hash::BLOCK_LENGTH_BYTES;
+
+static DETECTED: OnceLock<Backend> = OnceLock::new();
+
+static FORCE_PORTABLE: AtomicBool = AtomicBool::new(false);
+
+/// An implementation of the compression function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The portable implementation, available on every platform.
+    Portable,
+    /// The implementation using the x86 SHA extensions (SHA-NI).
+    ShaNi,
+    /// The implementation using the ARMv8 cryptography extensions.
+    ArmV8,
+}
+
+impl Backend {
+    /// Checks whether the current CPU supports the backend.
+    #[must_use]
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::Portable => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::ShaNi => x86::is_supported(),
+            #[cfg(target_arch = "aarch64")]
+            Self::ArmV8 => aarch64::is_supported(),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Backend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Portable => "portable",
+            Self::ShaNi => "sha-ni",
+            Self::ArmV8 => "armv8",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Returns the fastest backend supported by the current CPU.
+///
+/// The CPU features are detected on the first call only.
+#[must_use]
+pub fn detected() -> Backend {
+    *DETECTED.get_or_init(|| {
+        [Backend::ShaNi, Backend::ArmV8]
+            .into_iter()
+            .find(|backend| backend.is_supported())
+            .unwrap_or(Backend::Portable)
+    })
+}
+
+/// Returns the backend used by newly created hashes.
+///
+/// This is the [`detected`] backend unless the portable one is forced with [`force_portable`].
+#[must_use]
+pub fn active() -> Backend {
+    if FORCE_PORTABLE.load(Ordering::Relaxed) {
+        Backend::Portable
+    } else {
+        detected()
+    }
+}
+
+/// Forces usage of the portable backend for all hashes subsequently created in the process.
+///
+/// Passing `false` restores the runtime detection. Existing hashes keep the backend they were created with.
+pub fn force_portable(force: bool) {
+    FORCE_PORTABLE.store(force, Ordering::Relaxed);
+}
+
+/// Processes the given blocks with the given backend.
+///
+/// The backend must be supported by the CPU, which is checked when the [`Update`] is created.
+fn compress(backend: Backend, state: &mut [u32; 8], blocks: &[u8]) {
+    debug_assert_eq!(
+        blocks.len() % BLOCK_LENGTH_BYTES,
+        0,
+        "blocks length must be multiple of block length"
+    );
+    match backend {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::ShaNi => x86::compress(state, blocks),
+        #[cfg(target_arch = "aarch64")]
+        Backend::ArmV8 => aarch64::compress(state, blocks),
+        _ => portable::compress(state, blocks),
+    }
+}
+
+/// The hash state processed with the backend captured at creation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Update {
+    backend: Backend,
+    state: [u32; 8],
+    unprocessed: [u8; BLOCK_LENGTH_BYTES],
+    unprocessed_length: usize,
+    processed: u64,
+}
+
+impl Update {
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self::with_backend(active())
+    }
+
+    /// Creates the hash state processed with the given backend.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the backend isn't supported by the CPU.
+    #[must_use]
+    pub(crate) fn with_backend(backend: Backend) -> Self {
+        assert!(backend.is_supported(), "CPU must support the {backend} backend");
+        Self {
+            backend,
+            state: H,
+            unprocessed: [0u8; BLOCK_LENGTH_BYTES],
+            unprocessed_length: 0,
+            processed: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: impl AsRef<[u8]>) {
+        let mut data = data.as_ref();
+
+        if self.unprocessed_length > 0 {
+            let missing = BLOCK_LENGTH_BYTES - self.unprocessed_length;
+            let length = missing.min(data.len());
+            let (fillment, rest) = data.split_at(length);
+            self.unprocessed[self.unprocessed_length..self.unprocessed_length + length].copy_from_slice(fillment);
+            self.unprocessed_length += length;
+            data = rest;
+            if self.unprocessed_length < BLOCK_LENGTH_BYTES {
+                return;
+            }
+            compress(self.backend, &mut self.state, &self.unprocessed);
+            self.processed = self.processed.wrapping_add(BLOCK_LENGTH_BYTES as u64);
+            self.unprocessed_length = 0;
+        }
+
+        let length = data.len() - data.len() % BLOCK_LENGTH_BYTES;
+        let (blocks, remainder) = data.split_at(length);
+        if !blocks.is_empty() {
+            compress(self.backend, &mut self.state, blocks);
+            self.processed = self.processed.wrapping_add(blocks.len() as u64);
+        }
+        self.unprocessed[..remainder.len()].copy_from_slice(remainder);
+        self.unprocessed_length = remainder.len();
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::with_backend(self.backend);
+    }
+
+    #[must_use]
+    pub(crate) const fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    #[must_use]
+    pub(crate) fn digest(&self) -> hash::Digest {
+        let mut state = self.state;
+
+        let length = self.processed.wrapping_add(self.unprocessed_length as u64);
+        let length = length.wrapping_mul(8).to_be_bytes(); // convert byte-length into bits-length
+
+        let mut padding = [0u8; BLOCK_LENGTH_BYTES * 2];
+        padding[..self.unprocessed_length].copy_from_slice(&self.unprocessed[..self.unprocessed_length]);
+        padding[self.unprocessed_length] = 0x80;
+        let padding = if self.unprocessed_length + 1 + length.len() <= BLOCK_LENGTH_BYTES {
+            &mut padding[..BLOCK_LENGTH_BYTES]
+        } else {
+            &mut padding[..]
+        };
+        let offset = padding.len() - length.len();
+        padding[offset..].copy_from_slice(&length);
+        compress(self.backend, &mut state, padding);
+
+        let mut digest = [0u8; hash::DIGEST_LENGTH_BYTES];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        hash::Digest::new(digest)
+    }
+}
+
+impl Default for Update {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,88 @@
+#![allow(unsafe_code)]
+
+use std::arch::aarch64::{
+    vaddq_u32,
+    vld1q_u32,
+    vld1q_u8,
+    vreinterpretq_u32_u8,
+    vrev32q_u8,
+    vsha256h2q_u32,
+    vsha256hq_u32,
+    vsha256su0q_u32,
+    vsha256su1q_u32,
+    vst1q_u32,
+};
+use std::arch::is_aarch64_feature_detected;
+
+use super::BLOCK_LENGTH_BYTES;
+use crate::constants::K;
+
+/// Checks whether the CPU supports all extensions used by [`compress`].
+pub(super) fn is_supported() -> bool {
+    is_aarch64_feature_detected!("sha2")
+}
+
+/// Processes the given blocks with the ARMv8 cryptography extensions.
+pub(super) fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    debug_assert!(is_supported(), "CPU must support ARMv8 cryptography extensions");
+    // SAFETY: The backend is only used when the required CPU features are supported, which is checked once when the
+    // hash is created.
+    unsafe { compress_armv8(state, blocks) }
+}
+
+#[target_feature(enable = "sha2")]
+unsafe fn compress_armv8(state: &mut [u32; 8], blocks: &[u8]) {
+    let mut abcd = vld1q_u32(state[0..4].as_ptr());
+    let mut efgh = vld1q_u32(state[4..8].as_ptr());
+
+    for block in blocks.chunks_exact(BLOCK_LENGTH_BYTES) {
+        let abcd_save = abcd;
+        let efgh_save = efgh;
+
+        // the message words are big-endian
+        let mut s0 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[0..16].as_ptr())));
+        let mut s1 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[16..32].as_ptr())));
+        let mut s2 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[32..48].as_ptr())));
+        let mut s3 = vreinterpretq_u32_u8(vrev32q_u8(vld1q_u8(block[48..64].as_ptr())));
+
+        for i in (0..64).step_by(16) {
+            if i > 0 {
+                s0 = vsha256su1q_u32(vsha256su0q_u32(s0, s1), s2, s3);
+            }
+            let t = vaddq_u32(s0, vld1q_u32(K[i..i + 4].as_ptr()));
+            let abcd_prev = abcd;
+            abcd = vsha256hq_u32(abcd_prev, efgh, t);
+            efgh = vsha256h2q_u32(efgh, abcd_prev, t);
+
+            if i > 0 {
+                s1 = vsha256su1q_u32(vsha256su0q_u32(s1, s2), s3, s0);
+            }
+            let t = vaddq_u32(s1, vld1q_u32(K[i + 4..i + 8].as_ptr()));
+            let abcd_prev = abcd;
+            abcd = vsha256hq_u32(abcd_prev, efgh, t);
+            efgh = vsha256h2q_u32(efgh, abcd_prev, t);
+
+            if i > 0 {
+                s2 = vsha256su1q_u32(vsha256su0q_u32(s2, s3), s0, s1);
+            }
+            let t = vaddq_u32(s2, vld1q_u32(K[i + 8..i + 12].as_ptr()));
+            let abcd_prev = abcd;
+            abcd = vsha256hq_u32(abcd_prev, efgh, t);
+            efgh = vsha256h2q_u32(efgh, abcd_prev, t);
+
+            if i > 0 {
+                s3 = vsha256su1q_u32(vsha256su0q_u32(s3, s0), s1, s2);
+            }
+            let t = vaddq_u32(s3, vld1q_u32(K[i + 12..i + 16].as_ptr()));
+            let abcd_prev = abcd;
+            abcd = vsha256hq_u32(abcd_prev, efgh, t);
+            efgh = vsha256h2q_u32(efgh, abcd_prev, t);
+        }
+
+        abcd = vaddq_u32(abcd, abcd_save);
+        efgh = vaddq_u32(efgh, efgh_save);
+    }
+
+    vst1q_u32(state[0..4].as_mut_ptr(), abcd);
+    vst1q_u32(state[4..8].as_mut_ptr(), efgh);
+}
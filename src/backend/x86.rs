@@ -0,0 +1,134 @@
+#![allow(unsafe_code)]
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::{
+    __m128i,
+    _mm_add_epi32,
+    _mm_alignr_epi8,
+    _mm_blend_epi16,
+    _mm_loadu_si128,
+    _mm_set_epi32,
+    _mm_set_epi64x,
+    _mm_sha256msg1_epu32,
+    _mm_sha256msg2_epu32,
+    _mm_sha256rnds2_epu32,
+    _mm_shuffle_epi32,
+    _mm_shuffle_epi8,
+    _mm_storeu_si128,
+};
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{
+    __m128i,
+    _mm_add_epi32,
+    _mm_alignr_epi8,
+    _mm_blend_epi16,
+    _mm_loadu_si128,
+    _mm_set_epi32,
+    _mm_set_epi64x,
+    _mm_sha256msg1_epu32,
+    _mm_sha256msg2_epu32,
+    _mm_sha256rnds2_epu32,
+    _mm_shuffle_epi32,
+    _mm_shuffle_epi8,
+    _mm_storeu_si128,
+};
+
+use super::BLOCK_LENGTH_BYTES;
+use crate::constants::K;
+
+/// Checks whether the CPU supports all extensions used by [`compress`].
+pub(super) fn is_supported() -> bool {
+    is_x86_feature_detected!("sha")
+        && is_x86_feature_detected!("sse2")
+        && is_x86_feature_detected!("ssse3")
+        && is_x86_feature_detected!("sse4.1")
+}
+
+/// Processes the given blocks with the SHA extensions.
+pub(super) fn compress(state: &mut [u32; 8], blocks: &[u8]) {
+    debug_assert!(is_supported(), "CPU must support SHA extensions");
+    // SAFETY: The backend is only used when the required CPU features are supported, which is checked once when the
+    // hash is created.
+    unsafe { compress_sha_ni(state, blocks) }
+}
+
+/// Updates the message schedule for the next four rounds.
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+unsafe fn schedule(w0: __m128i, w1: __m128i, w2: __m128i, w3: __m128i) -> __m128i {
+    let t1 = _mm_sha256msg1_epu32(w0, w1);
+    let t2 = _mm_alignr_epi8(w3, w2, 4);
+    let t3 = _mm_add_epi32(t1, t2);
+    _mm_sha256msg2_epu32(t3, w3)
+}
+
+/// Performs four rounds of the compression function.
+#[inline]
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+#[allow(clippy::cast_possible_wrap)]
+unsafe fn rounds(abef: &mut __m128i, cdgh: &mut __m128i, w: __m128i, i: usize) {
+    let k = _mm_set_epi32(
+        K[i * 4 + 3] as i32,
+        K[i * 4 + 2] as i32,
+        K[i * 4 + 1] as i32,
+        K[i * 4] as i32,
+    );
+    let t1 = _mm_add_epi32(w, k);
+    *cdgh = _mm_sha256rnds2_epu32(*cdgh, *abef, t1);
+    let t2 = _mm_shuffle_epi32(t1, 0x0E);
+    *abef = _mm_sha256rnds2_epu32(*abef, *cdgh, t2);
+}
+
+#[target_feature(enable = "sha,sse2,ssse3,sse4.1")]
+#[allow(clippy::cast_possible_wrap, clippy::cast_ptr_alignment)]
+unsafe fn compress_sha_ni(state: &mut [u32; 8], blocks: &[u8]) {
+    let mask = _mm_set_epi64x(0x0C0D_0E0F_0809_0A0B_u64 as i64, 0x0405_0607_0001_0203_u64 as i64);
+
+    // unaligned loads and stores are used, so pointer alignment does not matter
+    let state_ptr = state.as_ptr().cast::<__m128i>();
+    let dcba = _mm_loadu_si128(state_ptr);
+    let efgh = _mm_loadu_si128(state_ptr.add(1));
+
+    let cdab = _mm_shuffle_epi32(dcba, 0xB1);
+    let efgh = _mm_shuffle_epi32(efgh, 0x1B);
+    let mut abef = _mm_alignr_epi8(cdab, efgh, 8);
+    let mut cdgh = _mm_blend_epi16(efgh, cdab, 0xF0);
+
+    for block in blocks.chunks_exact(BLOCK_LENGTH_BYTES) {
+        let abef_save = abef;
+        let cdgh_save = cdgh;
+
+        let block_ptr = block.as_ptr().cast::<__m128i>();
+        let mut w0 = _mm_shuffle_epi8(_mm_loadu_si128(block_ptr), mask);
+        let mut w1 = _mm_shuffle_epi8(_mm_loadu_si128(block_ptr.add(1)), mask);
+        let mut w2 = _mm_shuffle_epi8(_mm_loadu_si128(block_ptr.add(2)), mask);
+        let mut w3 = _mm_shuffle_epi8(_mm_loadu_si128(block_ptr.add(3)), mask);
+
+        rounds(&mut abef, &mut cdgh, w0, 0);
+        rounds(&mut abef, &mut cdgh, w1, 1);
+        rounds(&mut abef, &mut cdgh, w2, 2);
+        rounds(&mut abef, &mut cdgh, w3, 3);
+        for i in (4..16).step_by(4) {
+            w0 = schedule(w0, w1, w2, w3);
+            rounds(&mut abef, &mut cdgh, w0, i);
+            w1 = schedule(w1, w2, w3, w0);
+            rounds(&mut abef, &mut cdgh, w1, i + 1);
+            w2 = schedule(w2, w3, w0, w1);
+            rounds(&mut abef, &mut cdgh, w2, i + 2);
+            w3 = schedule(w3, w0, w1, w2);
+            rounds(&mut abef, &mut cdgh, w3, i + 3);
+        }
+
+        abef = _mm_add_epi32(abef, abef_save);
+        cdgh = _mm_add_epi32(cdgh, cdgh_save);
+    }
+
+    let feba = _mm_shuffle_epi32(abef, 0x1B);
+    let dchg = _mm_shuffle_epi32(cdgh, 0xB1);
+    let dcba = _mm_blend_epi16(feba, dchg, 0xF0);
+    let hgef = _mm_alignr_epi8(dchg, feba, 8);
+
+    let state_ptr = state.as_mut_ptr().cast::<__m128i>();
+    _mm_storeu_si128(state_ptr, dcba);
+    _mm_storeu_si128(state_ptr.add(1), hgef);
+}
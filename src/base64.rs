@@ -0,0 +1,49 @@
+//! The standard Base64 encoding with padding, as defined by [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648).
+
+/// The encoding alphabet.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes the data.
+pub(crate) fn encode(data: impl AsRef<[u8]>) -> String {
+    let data = data.as_ref();
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3F;
+                encoded.push(char::from(ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes the data, accepting missing padding.
+///
+/// Returns `None` when the input contains characters outside of the alphabet or has invalid length.
+pub(crate) fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    let trimmed = match encoded {
+        [rest @ .., b'=', b'='] | [rest @ .., b'='] if encoded.len() % 4 == 0 => rest,
+        _ => encoded,
+    };
+    if trimmed.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(trimmed.len() / 4 * 3 + 2);
+    for chunk in trimmed.chunks(4) {
+        let mut group = 0u32;
+        for (index, &byte) in chunk.iter().enumerate() {
+            let sextet = ALPHABET.iter().position(|&character| character == byte)?;
+            group |= (sextet as u32) << (18 - 6 * index);
+        }
+        let bytes = group.to_be_bytes();
+        decoded.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(decoded)
+}
@@ -0,0 +1,384 @@
+//! Caching of file digests keyed by file metadata.
+//!
+//! The [`DigestCache`] remembers the digest of a file together with its device, inode, size, modification time and
+//! status change time. As long as none of these change, the cached digest is returned without reading the file
+//! again, which saves most of the I/O when hashing trees that rarely change, e.g. build inputs.
+//!
+//! # Racy Entries
+//!
+//! A file modified within the same timestamp granularity right after it was hashed keeps its modification time, so
+//! its metadata alone can't tell the change apart. Like git does for its index, the cache treats an entry as
+//! *racily clean* when the file's modification or status change time isn't older than the moment the file was
+//! hashed, compared at the granularity of whole seconds. Such entries are never trusted; the file is hashed again
+//! and the entry becomes clean once the timestamps are old enough.
+//!
+//! # Platform Support
+//!
+//! On Unix all metadata fields are used. On other platforms the device and inode are not available and the
+//! modification time stands in for the status change time, so replacing a file with another one of the same size and
+//! modification time isn't detected.
+//!
+//! # File Format
+//!
+//! The cache is stored as UTF-8 text. The first line is the `chksum-sha2-256 digest cache v1` header, followed by
+//! one line per entry with space-separated fields:
+//!
+//! ```text
+//! <digest> <device> <inode> <size> <mtime> <ctime> <hashed> <path>
+//! ```
+//!
+//! The digest is in lowercase hexadecimal, timestamps are written as `<seconds>.<nanoseconds>` since the Unix epoch
+//! and the path takes the rest of the line with backslashes and line feeds escaped as `\\` and `\n`. Entries are
+//! sorted by path, so the same cache is always saved the same way. Entries of paths which aren't valid UTF-8 are kept
+//! in memory only.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::cache::DigestCache;
+//!
+//! # fn wrapper(cache_file: &Path, paths: &[&Path]) -> Result<()> {
+//! let mut cache = DigestCache::load(cache_file)?;
+//! for path in paths {
+//!     let digest = cache.chksum(path)?;
+//!     println!("{digest}  {}", path.display());
+//! }
+//! cache.prune();
+//! cache.save(cache_file)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::io::{self, ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::escape::{escape, unescape};
+use crate::{chksum_with, temporary, ChksumOptions, Digest, Result};
+
+/// The first line of the cache file.
+const HEADER: &str = "chksum-sha2-256 digest cache v1";
+
+/// A timestamp as seconds and nanoseconds since the Unix epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Timestamp {
+    seconds: i64,
+    nanoseconds: u32,
+}
+
+impl Timestamp {
+    /// Converts the system time, times before the Unix epoch are negative.
+    fn from_system_time(time: SystemTime) -> Self {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => {
+                Self {
+                    seconds: i64::try_from(duration.as_secs()).unwrap_or(i64::MAX),
+                    nanoseconds: duration.subsec_nanos(),
+                }
+            },
+            Err(error) => {
+                let duration = error.duration();
+                let seconds = -i64::try_from(duration.as_secs()).unwrap_or(i64::MAX);
+                match duration.subsec_nanos() {
+                    0 => {
+                        Self {
+                            seconds,
+                            nanoseconds: 0,
+                        }
+                    },
+                    nanoseconds => {
+                        Self {
+                            seconds: seconds - 1,
+                            nanoseconds: 1_000_000_000 - nanoseconds,
+                        }
+                    },
+                }
+            },
+        }
+    }
+
+    /// Parses the `<seconds>.<nanoseconds>` form.
+    fn parse(value: &str) -> Option<Self> {
+        let (seconds, nanoseconds) = value.split_once('.')?;
+        let seconds = seconds.parse().ok()?;
+        let nanoseconds = nanoseconds
+            .parse()
+            .ok()
+            .filter(|nanoseconds| *nanoseconds < 1_000_000_000)?;
+        Some(Self { seconds, nanoseconds })
+    }
+}
+
+/// The metadata identifying a version of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Stat {
+    device: u64,
+    inode: u64,
+    size: u64,
+    mtime: Timestamp,
+    ctime: Timestamp,
+}
+
+impl Stat {
+    #[cfg(unix)]
+    fn new(metadata: &Metadata) -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let timestamp = |seconds, nanoseconds: i64| {
+            Timestamp {
+                seconds,
+                nanoseconds: nanoseconds as u32,
+            }
+        };
+        Self {
+            device: metadata.dev(),
+            inode: metadata.ino(),
+            size: metadata.len(),
+            mtime: timestamp(metadata.mtime(), metadata.mtime_nsec()),
+            ctime: timestamp(metadata.ctime(), metadata.ctime_nsec()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn new(metadata: &Metadata) -> Self {
+        let mtime = metadata
+            .modified()
+            .map_or(Timestamp::from_system_time(UNIX_EPOCH), Timestamp::from_system_time);
+        Self {
+            device: 0,
+            inode: 0,
+            size: metadata.len(),
+            mtime,
+            ctime: mtime,
+        }
+    }
+}
+
+/// A cached digest of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Entry {
+    digest: Digest,
+    stat: Stat,
+    hashed: Timestamp,
+}
+
+impl Entry {
+    /// Returns `true` if the file could have been modified after hashing without changing its timestamps.
+    fn is_racy(&self) -> bool {
+        self.stat.mtime.seconds >= self.hashed.seconds || self.stat.ctime.seconds >= self.hashed.seconds
+    }
+}
+
+/// A cache of file digests keyed by path and file metadata.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DigestCache {
+    entries: HashMap<PathBuf, Entry>,
+}
+
+impl DigestCache {
+    /// Creates an empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the cache from the given file.
+    ///
+    /// An empty cache is returned when the file doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::InvalidData`] when the file isn't a valid cache, or any error from reading
+    /// the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::new()),
+            Err(error) => return Err(error.into()),
+        };
+        let mut lines = content.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(io::Error::new(ErrorKind::InvalidData, "missing digest cache header").into());
+        }
+        let mut entries = HashMap::new();
+        for (number, line) in lines.enumerate() {
+            let (path, entry) = parse_line(line).ok_or_else(|| {
+                // the header is the first line
+                let message = format!("invalid digest cache entry on line {}", number + 2);
+                io::Error::new(ErrorKind::InvalidData, message)
+            })?;
+            entries.insert(path, entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Saves the cache to the given file.
+    ///
+    /// The cache is written to a temporary file renamed afterwards, so a cache file is never partially written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|(path, entry)| Some((path.to_str()?, entry)))
+            .collect();
+        entries.sort_unstable_by_key(|(path, _)| *path);
+
+        let mut content = String::from(HEADER);
+        content.push('\n');
+        for (path, entry) in entries {
+            content.push_str(&format_line(path, entry));
+            content.push('\n');
+        }
+
+        let (mut file, temporary) = temporary::create(path)?;
+        let result = file
+            .write_all(content.as_bytes())
+            .and_then(|()| file.sync_all())
+            .and_then(|()| fs::rename(&temporary, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        Ok(result?)
+    }
+
+    /// Returns the number of cached entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the cached digest of the file if its metadata is unchanged and the entry isn't racily clean.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the metadata of the file cannot be read.
+    pub fn get(&self, path: impl AsRef<Path>) -> Result<Option<Digest>> {
+        let path = path.as_ref();
+        let Some(entry) = self.entries.get(path) else {
+            return Ok(None);
+        };
+        let stat = Stat::new(&fs::metadata(path)?);
+        Ok(Some(entry.digest).filter(|_| entry.stat == stat && !entry.is_racy()))
+    }
+
+    /// Computes the hash of the given path, using the cached digest when possible.
+    ///
+    /// Only regular files are cached, other paths like directories are hashed as by [`chksum`](crate::chksum) on
+    /// every call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the path cannot be read.
+    pub fn chksum(&mut self, path: impl AsRef<Path>) -> Result<Digest> {
+        self.chksum_with(path, &mut ChksumOptions::new())
+    }
+
+    /// Computes the hash of the given path using the provided options, using the cached digest when possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the path cannot be read.
+    pub fn chksum_with(&mut self, path: impl AsRef<Path>, options: &mut ChksumOptions) -> Result<Digest> {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path)?;
+        if !metadata.is_file() {
+            self.entries.remove(path);
+            return chksum_with(path, options);
+        }
+        let stat = Stat::new(&metadata);
+        if let Some(entry) = self.entries.get(path) {
+            if entry.stat == stat && !entry.is_racy() {
+                return Ok(entry.digest);
+            }
+        }
+
+        let hashed = Timestamp::from_system_time(SystemTime::now());
+        let digest = chksum_with(path, options)?;
+        // the file changed while it was read, the digest may not match any version of it
+        if Stat::new(&fs::metadata(path)?) == stat {
+            let entry = Entry { digest, stat, hashed };
+            self.entries.insert(path.to_path_buf(), entry);
+        } else {
+            self.entries.remove(path);
+        }
+        Ok(digest)
+    }
+
+    /// Removes the entry of the given path, returning `true` if it was cached.
+    pub fn invalidate(&mut self, path: impl AsRef<Path>) -> bool {
+        self.entries.remove(path.as_ref()).is_some()
+    }
+
+    /// Retains only the entries of paths for which the predicate returns `true`.
+    pub fn retain(&mut self, mut predicate: impl FnMut(&Path) -> bool) {
+        self.entries.retain(|path, _| predicate(path));
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Removes the entries of files which no longer exist or whose metadata changed, returning the number of removed
+    /// entries.
+    pub fn prune(&mut self) -> usize {
+        let length = self.entries.len();
+        self.entries.retain(|path, entry| {
+            fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && Stat::new(&metadata) == entry.stat)
+        });
+        length - self.entries.len()
+    }
+}
+
+/// Formats an entry as a line of the cache file.
+fn format_line(path: &str, entry: &Entry) -> String {
+    let Entry { digest, stat, hashed } = entry;
+    let timestamp = |timestamp: &Timestamp| format!("{}.{:09}", timestamp.seconds, timestamp.nanoseconds);
+    let path = escape(path);
+    format!(
+        "{digest:x} {} {} {} {} {} {} {path}",
+        stat.device,
+        stat.inode,
+        stat.size,
+        timestamp(&stat.mtime),
+        timestamp(&stat.ctime),
+        timestamp(hashed),
+    )
+}
+
+/// Parses a line of the cache file.
+fn parse_line(line: &str) -> Option<(PathBuf, Entry)> {
+    let mut fields = line.splitn(8, ' ');
+    let mut field = || fields.next();
+    let digest = field()?;
+    if digest.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        return None;
+    }
+    let digest = Digest::try_from(digest).ok()?;
+    let stat = Stat {
+        device: field()?.parse().ok()?,
+        inode: field()?.parse().ok()?,
+        size: field()?.parse().ok()?,
+        mtime: Timestamp::parse(field()?)?,
+        ctime: Timestamp::parse(field()?)?,
+    };
+    let hashed = Timestamp::parse(field()?)?;
+    let path = unescape(field()?)?;
+    Some((path.into(), Entry { digest, stat, hashed }))
+}
@@ -0,0 +1,377 @@
+//! This module is optional and can be enabled using the `cas` Cargo feature.
+//!
+//! The [`Store`] keeps blobs in a local directory under their SHA-2 256 digests. Blobs are spread over 256
+//! subdirectories named after the first byte of the digest, e.g. the blob with digest `3a6eb079...` is stored as
+//! `3a/6eb079...`, so no single directory grows too large.
+//!
+//! Inserts are atomic: the data is hashed while it is written to a temporary file, which is then renamed into place,
+//! so a blob file always has the content its name promises. Inserting a blob which is already stored keeps the
+//! existing file. Reads re-verify the content with a [`BlobReader`], which reports corruption, e.g. caused by disk
+//! errors, instead of returning wrong data.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `cas` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["cas"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features cas
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! use std::collections::HashSet;
+//!
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::cas::Store;
+//!
+//! # fn wrapper(path: &Path) -> Result<()> {
+//! let store = Store::open(path)?;
+//! let digest = store.insert(b"example data")?;
+//! assert_eq!(
+//!     digest.to_hex_lowercase(),
+//!     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+//! );
+//! assert_eq!(store.read(&digest)?, b"example data");
+//!
+//! let reachable = HashSet::from([digest]);
+//! let removed = store.gc(&reachable)?;
+//! assert!(removed.is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::verifying::VerifyingReader;
+use crate::writer::AtomicWriter;
+use crate::{Digest, Result, SHA2_256};
+
+/// The name of the directory holding temporary files of inserts in progress.
+const TEMPORARY_DIR: &str = "tmp";
+
+/// The length of the fan-out directory names.
+const FAN_OUT_LENGTH: usize = 2;
+
+/// A local content-addressable store.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    /// The age after which [`gc`](Self::gc) removes temporary files of inserts.
+    pub const TEMPORARY_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Opens the store in the given directory, creating the directory if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the directory cannot be created.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join(TEMPORARY_DIR))?;
+        Ok(Self { root })
+    }
+
+    /// Returns the root directory of the store.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the path of the blob with the given digest.
+    ///
+    /// The blob doesn't need to exist.
+    #[must_use]
+    pub fn path(&self, digest: &Digest) -> PathBuf {
+        let hex = digest.to_hex_lowercase();
+        let (fan_out, name) = hex.split_at(FAN_OUT_LENGTH);
+        self.root.join(fan_out).join(name)
+    }
+
+    /// Returns `true` if the blob with the given digest is stored.
+    #[must_use]
+    pub fn contains(&self, digest: &Digest) -> bool {
+        self.path(digest).is_file()
+    }
+
+    /// Stores the given data, returning its digest.
+    ///
+    /// Nothing is written when the blob is already stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the blob cannot be written.
+    pub fn insert(&self, data: impl AsRef<[u8]>) -> Result<Digest> {
+        let data = data.as_ref();
+        let digest = SHA2_256::hash(data);
+        if self.contains(&digest) {
+            return Ok(digest);
+        }
+        let mut writer = self.writer()?;
+        writer.write_all(data)?;
+        writer.commit()
+    }
+
+    /// Stores all data read from the given reader, returning its digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when reading from the reader fails or the blob cannot be written.
+    pub fn insert_reader(&self, mut reader: impl Read) -> Result<Digest> {
+        let mut writer = self.writer()?;
+        io::copy(&mut reader, &mut writer)?;
+        writer.commit()
+    }
+
+    /// Creates a writer inserting a new blob once committed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the temporary file cannot be created.
+    pub fn writer(&self) -> Result<BlobWriter> {
+        let inner = AtomicWriter::create(self.root.join(TEMPORARY_DIR).join("blob"))?;
+        let store = self.clone();
+        Ok(BlobWriter { inner, store })
+    }
+
+    /// Opens the blob with the given digest for reading.
+    ///
+    /// The content is verified while it is read, see [`BlobReader`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::NotFound`] when the blob isn't stored.
+    pub fn open_blob(&self, digest: &Digest) -> Result<BlobReader<File>> {
+        let file = File::open(self.path(digest))?;
+        Ok(BlobReader::new(file, *digest))
+    }
+
+    /// Reads the whole blob with the given digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::NotFound`] when the blob isn't stored, or [`ErrorKind::InvalidData`] when
+    /// its content doesn't match the digest.
+    pub fn read(&self, digest: &Digest) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.open_blob(digest)?.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Verifies the content of the blob with the given digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::NotFound`] when the blob isn't stored, or [`ErrorKind::InvalidData`] when
+    /// its content doesn't match the digest.
+    pub fn verify(&self, digest: &Digest) -> Result<()> {
+        io::copy(&mut self.open_blob(digest)?, &mut io::sink())?;
+        Ok(())
+    }
+
+    /// Removes the blob with the given digest, returning `true` if it was stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the blob cannot be removed.
+    pub fn remove(&self, digest: &Digest) -> Result<bool> {
+        match fs::remove_file(self.path(digest)) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Returns the digests of all stored blobs in ascending order.
+    ///
+    /// Files which don't follow the layout of the store are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the store cannot be read.
+    pub fn digests(&self) -> Result<Vec<Digest>> {
+        let mut digests = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let Some(fan_out) = entry.file_name().to_str().map(str::to_owned) else {
+                continue;
+            };
+            if fan_out.len() != FAN_OUT_LENGTH || !entry.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(entry.path())? {
+                let entry = entry?;
+                let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+                    continue;
+                };
+                let hex = fan_out.clone() + &name;
+                // uppercase names are rejected, `path` always produces lowercase ones
+                if hex.bytes().any(|byte| byte.is_ascii_uppercase()) || !entry.file_type()?.is_file() {
+                    continue;
+                }
+                if let Ok(digest) = Digest::try_from(hex.as_str()) {
+                    digests.push(digest);
+                }
+            }
+        }
+        digests.sort_unstable();
+        Ok(digests)
+    }
+
+    /// Removes all blobs which aren't in the reachable set, returning the digests of removed blobs.
+    ///
+    /// Empty fan-out directories are removed as well, and so are temporary files of inserts older than
+    /// [`TEMPORARY_MAX_AGE`](Self::TEMPORARY_MAX_AGE), which are left behind by interrupted processes. Blobs inserted
+    /// concurrently with the collection may be removed if they aren't reachable, so callers should not run it while
+    /// inserts are in progress.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the store cannot be read or a blob cannot be removed.
+    pub fn gc(&self, reachable: &HashSet<Digest>) -> Result<Vec<Digest>> {
+        self.remove_temporary(Self::TEMPORARY_MAX_AGE)?;
+        let mut removed = Vec::new();
+        for digest in self.digests()? {
+            if reachable.contains(&digest) {
+                continue;
+            }
+            let path = self.path(&digest);
+            fs::remove_file(&path)?;
+            if let Some(parent) = path.parent() {
+                // fails when the directory still holds other blobs
+                let _ = fs::remove_dir(parent);
+            }
+            removed.push(digest);
+        }
+        Ok(removed)
+    }
+
+    /// Removes temporary files of inserts which weren't modified for at least the given time, returning the number
+    /// of removed files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the temporary directory cannot be read or a file cannot be removed.
+    pub fn remove_temporary(&self, max_age: Duration) -> Result<usize> {
+        let mut removed = 0;
+        for entry in fs::read_dir(self.root.join(TEMPORARY_DIR))? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            // timestamps in the future are treated as fresh
+            let age = metadata.modified()?.elapsed().unwrap_or_default();
+            if metadata.is_file() && age >= max_age {
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => removed += 1,
+                    // committed or removed concurrently
+                    Err(error) if error.kind() == ErrorKind::NotFound => {},
+                    Err(error) => return Err(error.into()),
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// A writer inserting a blob into the [`Store`].
+///
+/// The data is hashed while it is written and the blob is moved into place by [`commit`](BlobWriter::commit).
+/// Dropping the writer without committing discards the data.
+#[derive(Debug)]
+pub struct BlobWriter {
+    inner: AtomicWriter,
+    store: Store,
+}
+
+impl BlobWriter {
+    /// Returns the digest of the data written so far.
+    #[must_use]
+    pub fn digest(&self) -> Digest {
+        self.inner.digest()
+    }
+
+    /// Moves the blob into the store, returning its digest.
+    ///
+    /// When the blob is already stored, the written data is discarded and the existing file is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the blob cannot be synced or moved into place.
+    pub fn commit(self) -> Result<Digest> {
+        let Self { inner, store } = self;
+        let digest = inner.digest();
+        let path = store.path(&digest);
+        if path.is_file() {
+            inner.abort()?;
+            return Ok(digest);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        inner.commit_to(&path)
+    }
+}
+
+impl Write for BlobWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader verifying the data against the expected digest.
+///
+/// The data is hashed while it is read. When the end of the data is reached and the digest doesn't match, an error
+/// with [`ErrorKind::InvalidData`] is returned instead of the end of file.
+#[derive(Debug)]
+pub struct BlobReader<R> {
+    inner: VerifyingReader<R>,
+}
+
+impl<R> BlobReader<R>
+where
+    R: Read,
+{
+    /// Creates new [`BlobReader`] verifying the data against the expected digest.
+    pub fn new(inner: R, expected: Digest) -> Self {
+        let inner = VerifyingReader::new(inner, expected, None);
+        Self { inner }
+    }
+
+    /// Returns the expected digest.
+    #[must_use]
+    pub const fn expected(&self) -> Digest {
+        self.inner.expected()
+    }
+
+    /// Unwraps this [`BlobReader`], returning the underlying reader.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R> Read for BlobReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
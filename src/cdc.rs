@@ -0,0 +1,376 @@
+//! The [`Chunker`] splits data into variable-size chunks with content-defined chunking and identifies each chunk by
+//! its SHA-2 256 digest, e.g. for deduplicating backups.
+//!
+//! Boundaries are found with the [FastCDC](https://www.usenix.org/conference/atc16/technical-sessions/presentation/xia)
+//! algorithm: a Gear rolling hash is computed over the data and a chunk ends where the hash matches a mask derived from
+//! the average chunk size. A stricter mask is used before the average size and a looser one after it, which keeps the
+//! chunk sizes close to the average. Chunks are never shorter than the minimum size, except for the last one, and never
+//! longer than the maximum size.
+//!
+//! Since boundaries depend on the content only, inserting data into a stream changes the chunks around the insertion
+//! point while the others stay the same. The Gear table is fixed, so boundaries are identical across platforms and
+//! versions of this crate.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! use std::fs::File;
+//!
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::cdc::{Chunker, ChunkerOptions};
+//!
+//! # fn wrapper(path: &Path) -> Result<()> {
+//! let file = File::open(path)?;
+//! let options = ChunkerOptions::new(16 * 1024, 64 * 1024, 256 * 1024);
+//! for chunk in Chunker::with_options(file, options) {
+//!     let chunk = chunk?;
+//!     println!("{} {} {}", chunk.offset(), chunk.length(), chunk.digest());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{ErrorKind, Read};
+
+use crate::{Digest, Result, SHA2_256};
+
+/// The Gear table of random values for each byte value.
+const GEAR: [u64; 256] = gear();
+
+/// Generates the Gear table with the SplitMix64 generator from a fixed seed.
+const fn gear() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut state: u64 = 0x6368_6B73_756D_6364; // "chksumcd"
+    let mut index = 0;
+    while index < table.len() {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[index] = value ^ (value >> 31);
+        index += 1;
+    }
+    table
+}
+
+/// Returns a mask of the given number of the most significant bits.
+///
+/// The high bits of the Gear hash depend on the most recent 64 bytes, the low bits only on the last few bytes.
+const fn mask(bits: u32) -> u64 {
+    if bits == 0 {
+        0
+    } else if bits >= u64::BITS {
+        u64::MAX
+    } else {
+        u64::MAX << (u64::BITS - bits)
+    }
+}
+
+/// The minimum, average and maximum chunk sizes of the [`Chunker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkerOptions {
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+}
+
+impl ChunkerOptions {
+    /// The default average chunk size.
+    pub const DEFAULT_AVG_SIZE: usize = 64 * 1024;
+    /// The default maximum chunk size.
+    pub const DEFAULT_MAX_SIZE: usize = 256 * 1024;
+    /// The default minimum chunk size.
+    pub const DEFAULT_MIN_SIZE: usize = 16 * 1024;
+
+    /// Creates options with the given minimum, average and maximum chunk sizes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the minimum size is zero or the sizes are not ordered from the minimum to the maximum.
+    #[must_use]
+    pub const fn new(min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        assert!(min_size > 0, "minimum chunk size must be greater than zero");
+        assert!(
+            min_size <= avg_size && avg_size <= max_size,
+            "chunk sizes must satisfy minimum <= average <= maximum"
+        );
+        Self {
+            min_size,
+            avg_size,
+            max_size,
+        }
+    }
+
+    /// Returns the minimum chunk size.
+    #[must_use]
+    pub const fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    /// Returns the average chunk size.
+    #[must_use]
+    pub const fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    /// Returns the maximum chunk size.
+    #[must_use]
+    pub const fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the length of the first chunk of the data.
+    ///
+    /// The data must hold at least the maximum chunk size unless it is the end of the input.
+    fn cut(&self, data: &[u8]) -> usize {
+        let length = data.len().min(self.max_size);
+        if length <= self.min_size {
+            return length;
+        }
+        let bits = self.avg_size.ilog2();
+        let (small, large) = (mask(bits + 2), mask(bits.saturating_sub(2)));
+        let normal = self.avg_size.min(length);
+
+        let mut hash = 0u64;
+        for (index, byte) in data.iter().enumerate().take(length).skip(self.min_size) {
+            hash = (hash << 1).wrapping_add(GEAR[usize::from(*byte)]);
+            let mask = if index < normal { small } else { large };
+            if hash & mask == 0 {
+                return index + 1;
+            }
+        }
+        length
+    }
+}
+
+impl Default for ChunkerOptions {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MIN_SIZE, Self::DEFAULT_AVG_SIZE, Self::DEFAULT_MAX_SIZE)
+    }
+}
+
+/// A content-defined chunk of the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chunk {
+    offset: u64,
+    length: usize,
+    digest: Digest,
+}
+
+impl Chunk {
+    /// Returns the offset of the chunk in the input.
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the length of the chunk.
+    #[must_use]
+    pub const fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the digest of the chunk.
+    #[must_use]
+    pub const fn digest(&self) -> Digest {
+        self.digest
+    }
+}
+
+impl From<Chunk> for (u64, usize, Digest) {
+    fn from(chunk: Chunk) -> Self {
+        (chunk.offset, chunk.length, chunk.digest)
+    }
+}
+
+/// The buffered data not yet split into chunks.
+#[derive(Debug)]
+struct Buffer {
+    options: ChunkerOptions,
+    data: Vec<u8>,
+    start: usize,
+    end: usize,
+    offset: u64,
+    eof: bool,
+}
+
+impl Buffer {
+    fn new(options: ChunkerOptions) -> Self {
+        Self {
+            options,
+            data: Vec::new(),
+            start: 0,
+            end: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    /// Returns `true` when more data is needed to find the next boundary.
+    fn needs_data(&self) -> bool {
+        !self.eof && self.end - self.start < self.options.max_size
+    }
+
+    /// Returns the free space for the next read, moving the pending data to the front of the buffer.
+    fn spare(&mut self) -> &mut [u8] {
+        if self.start > 0 {
+            self.data.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        if self.end == self.data.len() {
+            // grow with the input instead of allocating for the maximum chunk size up front, the pending data is
+            // shorter than the maximum chunk size so the buffer never exceeds twice of it
+            let limit = self.options.max_size.saturating_mul(2);
+            let capacity = self.data.len().saturating_mul(2).min(limit);
+            self.data.resize(capacity.max(crate::options::DEFAULT_BUFFER_SIZE), 0);
+        }
+        &mut self.data[self.end..]
+    }
+
+    /// Marks the given number of bytes of the free space as read, zero marks the end of the input.
+    fn filled(&mut self, length: usize) {
+        if length == 0 {
+            self.eof = true;
+        }
+        self.end += length;
+    }
+
+    /// Splits the next chunk off the buffered data.
+    fn chunk(&mut self) -> Option<Chunk> {
+        let data = &self.data[self.start..self.end];
+        if data.is_empty() {
+            return None;
+        }
+        let length = self.options.cut(data);
+        let digest = SHA2_256::hash(&data[..length]);
+        let chunk = Chunk {
+            offset: self.offset,
+            length,
+            digest,
+        };
+        self.start += length;
+        self.offset += length as u64;
+        Some(chunk)
+    }
+}
+
+/// An iterator splitting a reader into content-defined chunks.
+///
+/// # Example
+///
+/// ```rust
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256::cdc::{Chunker, ChunkerOptions};
+///
+/// # fn wrapper() -> Result<()> {
+/// let data = vec![0; 10_000];
+/// let options = ChunkerOptions::new(1_000, 2_000, 4_000);
+/// let lengths = Chunker::with_options(&data[..], options)
+///     .map(|chunk| chunk.map(|chunk| chunk.length()))
+///     .collect::<Result<Vec<_>>>()?;
+/// assert_eq!(lengths, [4_000, 4_000, 2_000]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Chunker<R> {
+    reader: R,
+    buffer: Buffer,
+}
+
+impl<R> Chunker<R>
+where
+    R: Read,
+{
+    /// Creates a chunker with the default chunk sizes.
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ChunkerOptions::default())
+    }
+
+    /// Creates a chunker with the given chunk sizes.
+    pub fn with_options(reader: R, options: ChunkerOptions) -> Self {
+        let buffer = Buffer::new(options);
+        Self { reader, buffer }
+    }
+
+    /// Unwraps this `Chunker<R>`, returning the underlying reader.
+    ///
+    /// Data which was read but not yet returned as chunks is lost.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for Chunker<R>
+where
+    R: Read,
+{
+    type Item = Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.needs_data() {
+            match self.reader.read(self.buffer.spare()) {
+                Ok(length) => self.buffer.filled(length),
+                Err(error) if error.kind() == ErrorKind::Interrupted => {},
+                Err(error) => return Some(Err(error.into())),
+            }
+        }
+        self.buffer.chunk().map(Ok)
+    }
+}
+
+/// A chunker splitting an async reader into content-defined chunks.
+///
+/// See [`Chunker`].
+#[cfg(feature = "async-runtime-tokio")]
+#[derive(Debug)]
+pub struct AsyncChunker<R> {
+    reader: R,
+    buffer: Buffer,
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+impl<R> AsyncChunker<R>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    /// Creates a chunker with the default chunk sizes.
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ChunkerOptions::default())
+    }
+
+    /// Creates a chunker with the given chunk sizes.
+    pub fn with_options(reader: R, options: ChunkerOptions) -> Self {
+        let buffer = Buffer::new(options);
+        Self { reader, buffer }
+    }
+
+    /// Returns the next chunk, or `None` at the end of the input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the reader cannot be read.
+    pub async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
+        use tokio::io::AsyncReadExt;
+
+        while self.buffer.needs_data() {
+            match self.reader.read(self.buffer.spare()).await {
+                Ok(length) => self.buffer.filled(length),
+                Err(error) if error.kind() == ErrorKind::Interrupted => {},
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(self.buffer.chunk())
+    }
+
+    /// Unwraps this `AsyncChunker<R>`, returning the underlying reader.
+    ///
+    /// Data which was read but not yet returned as chunks is lost.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
@@ -0,0 +1,303 @@
+//! The [`chksum_chunks`] function computes the digests of fixed-size chunks of the input together with the digest of
+//! the whole input in a single pass, e.g. for resumable or parallel transfers.
+//!
+//! A local copy is checked against the resulting [`ChunkDigests`] with [`verify_chunks`], which reports the indices
+//! of the corrupt chunks, so only those have to be transferred again.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! use std::fs::File;
+//!
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::chunks;
+//!
+//! # fn wrapper(source: &Path, copy: &Path) -> Result<()> {
+//! let digests = chunks::chksum_chunks(File::open(source)?, 4 * 1024 * 1024)?;
+//! println!(
+//!     "{} chunks, digest {}",
+//!     digests.chunks().len(),
+//!     digests.digest()
+//! );
+//!
+//! let corrupt = chunks::verify_chunks(File::open(copy)?, &digests)?;
+//! for index in corrupt {
+//!     println!("chunk {index} is corrupt");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "async-runtime-tokio")]
+use crate::AsyncChksumable;
+use crate::{core, Chksumable, Digest, Result, SHA2_256};
+
+/// The digests of fixed-size chunks of an input and the digest of the whole input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkDigests {
+    chunk_size: usize,
+    length: u64,
+    chunks: Vec<Digest>,
+    digest: Digest,
+}
+
+impl ChunkDigests {
+    /// Creates the chunk digests from already computed values, e.g. received from a remote peer.
+    #[must_use]
+    pub fn new(chunk_size: usize, length: u64, chunks: Vec<Digest>, digest: Digest) -> Self {
+        Self {
+            chunk_size,
+            length,
+            chunks,
+            digest,
+        }
+    }
+
+    /// Returns the size of the chunks, the last chunk may be shorter.
+    #[must_use]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns the length of the whole input.
+    #[must_use]
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns the digests of the chunks in the order of the input.
+    #[must_use]
+    pub fn chunks(&self) -> &[Digest] {
+        &self.chunks
+    }
+
+    /// Returns the digest of the whole input.
+    #[must_use]
+    pub fn digest(&self) -> Digest {
+        self.digest
+    }
+
+    /// Returns the indices of the chunks which differ from the given chunk digests.
+    ///
+    /// Chunks present in only one of the lists are reported too.
+    #[must_use]
+    pub fn corrupt_chunks(&self, other: &Self) -> Vec<usize> {
+        let count = self.chunks.len().max(other.chunks.len());
+        (0..count)
+            .filter(|&index| self.chunks.get(index) != other.chunks.get(index))
+            .collect()
+    }
+}
+
+/// Computes the digests of the chunks of `chunk_size` bytes and the digest of the whole input in a single pass.
+///
+/// The last chunk may be shorter. An empty input has no chunks.
+///
+/// # Example
+///
+/// ```rust
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256::chunks;
+///
+/// # fn wrapper() -> Result<()> {
+/// let digests = chunks::chksum_chunks(b"example data", 8)?;
+/// assert_eq!(digests.chunks().len(), 2);
+/// assert_eq!(
+///     digests.digest().to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the input cannot be read.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+pub fn chksum_chunks(mut data: impl Chksumable, chunk_size: usize) -> Result<ChunkDigests> {
+    let mut chunks = Chunks::new(chunk_size);
+    data.chksum_with(&mut chunks)?;
+    Ok(chunks.finish())
+}
+
+/// Computes the digests of the chunks of `chunk_size` bytes and the digest of the whole input in a single pass.
+///
+/// See [`chksum_chunks`].
+///
+/// # Errors
+///
+/// Returns an error when the input cannot be read.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_chksum_chunks(mut data: impl AsyncChksumable, chunk_size: usize) -> Result<ChunkDigests> {
+    let mut chunks = Chunks::new(chunk_size);
+    data.chksum_with(&mut chunks).await?;
+    Ok(chunks.finish())
+}
+
+/// Verifies the input against the expected chunk digests, returning the indices of the corrupt chunks.
+///
+/// The input is split into chunks of the expected chunk size. Chunks missing from a shorter input and chunks of
+/// excess data of a longer input are reported as corrupt. An empty result means the input is intact.
+///
+/// # Example
+///
+/// ```rust
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256::chunks;
+///
+/// # fn wrapper() -> Result<()> {
+/// let expected = chunks::chksum_chunks(b"example data", 4)?;
+/// assert!(chunks::verify_chunks(b"example data", &expected)?.is_empty());
+/// assert_eq!(chunks::verify_chunks(b"exam_le data", &expected)?, [0]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the input cannot be read.
+///
+/// # Panics
+///
+/// Panics if the expected chunk size is zero.
+pub fn verify_chunks(data: impl Chksumable, expected: &ChunkDigests) -> Result<Vec<usize>> {
+    let actual = chksum_chunks(data, expected.chunk_size)?;
+    Ok(expected.corrupt_chunks(&actual))
+}
+
+/// Verifies the input against the expected chunk digests, returning the indices of the corrupt chunks.
+///
+/// See [`verify_chunks`].
+///
+/// # Errors
+///
+/// Returns an error when the input cannot be read.
+///
+/// # Panics
+///
+/// Panics if the expected chunk size is zero.
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_verify_chunks(data: impl AsyncChksumable, expected: &ChunkDigests) -> Result<Vec<usize>> {
+    let actual = async_chksum_chunks(data, expected.chunk_size).await?;
+    Ok(expected.corrupt_chunks(&actual))
+}
+
+/// A splitter of the incoming data into fixed-size chunks, hashing each of them.
+///
+/// Every chunk hash starts with the prefix, which allows domain separation, e.g. of Merkle tree leaves.
+#[derive(Clone, Debug)]
+pub(crate) struct ChunkSplitter {
+    chunk_size: usize,
+    prefix: &'static [u8],
+    chunk: SHA2_256,
+    chunk_length: usize,
+    digests: Vec<Digest>,
+}
+
+impl ChunkSplitter {
+    /// Creates a splitter into chunks of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk size is zero.
+    pub(crate) fn new(chunk_size: usize, prefix: &'static [u8]) -> Self {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+        let mut chunk = SHA2_256::new();
+        chunk.update(prefix);
+        Self {
+            chunk_size,
+            prefix,
+            chunk,
+            chunk_length: 0,
+            digests: Vec::new(),
+        }
+    }
+
+    pub(crate) const fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let length = (self.chunk_size - self.chunk_length).min(data.len());
+            let (chunk, rest) = data.split_at(length);
+            self.chunk.update(chunk);
+            self.chunk_length += length;
+            data = rest;
+
+            if self.chunk_length == self.chunk_size {
+                self.digests.push(self.chunk.digest());
+                self.chunk.reset();
+                self.chunk.update(self.prefix);
+                self.chunk_length = 0;
+            }
+        }
+    }
+
+    /// Returns the digests of all chunks, including the last partial one.
+    pub(crate) fn finish(mut self) -> Vec<Digest> {
+        if self.chunk_length > 0 {
+            self.digests.push(self.chunk.digest());
+        }
+        self.digests
+    }
+}
+
+/// A hash which computes the digests of fixed-size chunks next to the digest of the whole data.
+#[derive(Clone, Debug)]
+struct Chunks {
+    splitter: ChunkSplitter,
+    whole: SHA2_256,
+    length: u64,
+}
+
+impl Chunks {
+    fn new(chunk_size: usize) -> Self {
+        Self {
+            splitter: ChunkSplitter::new(chunk_size, &[]),
+            whole: SHA2_256::new(),
+            length: 0,
+        }
+    }
+
+    fn finish(self) -> ChunkDigests {
+        let chunk_size = self.splitter.chunk_size();
+        ChunkDigests::new(chunk_size, self.length, self.splitter.finish(), self.whole.digest())
+    }
+}
+
+impl Default for Chunks {
+    fn default() -> Self {
+        Self::new(crate::options::DEFAULT_BUFFER_SIZE)
+    }
+}
+
+impl core::Hash for Chunks {
+    type Digest = Digest;
+
+    fn update<T>(&mut self, data: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        let data = data.as_ref();
+        self.whole.update(data);
+        self.length += data.len() as u64;
+        self.splitter.update(data);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.splitter.chunk_size());
+    }
+
+    fn digest(&self) -> Self::Digest {
+        self.whole.digest()
+    }
+}
@@ -0,0 +1,353 @@
+//! Integrity fields of HTTP messages as defined by [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530).
+//!
+//! The `Content-Digest` and `Repr-Digest` fields carry a structured field dictionary with digests of the message
+//! content or the selected representation, e.g. `sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:`. Use
+//! [`format`] or [`chksum`] to create the field value and [`parse`] or [`verify`] to check a received one. Members
+//! with algorithms other than `sha-256` are ignored.
+//!
+//! The obsolete `Digest` field from [RFC 3230](https://www.rfc-editor.org/rfc/rfc3230), e.g.
+//! `SHA-256=Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=`, is handled by [`format_legacy`], [`parse_legacy`] and
+//! [`verify_legacy`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::digest_fields;
+//!
+//! # use chksum_sha2_256::Result;
+//! # fn wrapper() -> Result<()> {
+//! let value = digest_fields::chksum(b"data")?;
+//! assert_eq!(
+//!     value,
+//!     "sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:"
+//! );
+//!
+//! digest_fields::verify(
+//!     "md5=:jXd/OF09/siBXSD3SWAm3A==:, sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:",
+//!     b"data",
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+#[cfg(feature = "writer")]
+use std::io::Write;
+use std::io::{self, ErrorKind};
+
+#[cfg(feature = "writer")]
+use crate::Writer;
+use crate::{base64, core, hash, Digest, Result, SHA2_256};
+
+/// The name of the field with the digest of the message content.
+pub const CONTENT_DIGEST: &str = "Content-Digest";
+
+/// The name of the field with the digest of the selected representation.
+pub const REPR_DIGEST: &str = "Repr-Digest";
+
+/// The name of the obsolete RFC 3230 field.
+pub const LEGACY_DIGEST: &str = "Digest";
+
+/// The algorithm key of the SHA-2 256 hash.
+pub const ALGORITHM: &str = "sha-256";
+
+/// An error returned when parsing of a field value fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The value isn't a valid structured field dictionary.
+    InvalidDictionary,
+    /// The `sha-256` member isn't a byte sequence with a 32 bytes long digest.
+    InvalidDigest,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::InvalidDictionary => "invalid dictionary",
+            Self::InvalidDigest => "invalid digest",
+        };
+        f.write_str(message)
+    }
+}
+
+impl StdError for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> Self {
+        Self::new(ErrorKind::InvalidData, error)
+    }
+}
+
+/// Formats the digest as a `Content-Digest` or `Repr-Digest` field value.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::{digest_fields, SHA2_256};
+///
+/// let value = digest_fields::format(SHA2_256::hash(b"data"));
+/// assert_eq!(
+///     value,
+///     "sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:"
+/// );
+/// ```
+#[must_use]
+pub fn format(digest: Digest) -> String {
+    format!("{ALGORITHM}=:{}:", base64::encode(digest))
+}
+
+/// Computes the digest of the given input and formats it as a field value.
+///
+/// # Errors
+///
+/// Returns an error when the input cannot be read.
+pub fn chksum(data: impl core::Chksumable) -> Result<String> {
+    core::chksum::<SHA2_256>(data).map(format)
+}
+
+/// Formats the digest of the data written so far as a field value.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Write;
+///
+/// use chksum_sha2_256::{digest_fields, writer};
+///
+/// # fn wrapper() -> std::io::Result<()> {
+/// let mut writer = writer::new(Vec::new());
+/// writer.write_all(b"data")?;
+/// assert_eq!(
+///     digest_fields::format_writer(&writer),
+///     "sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "writer")]
+#[must_use]
+pub fn format_writer<W>(writer: &Writer<W>) -> String
+where
+    W: Write,
+{
+    format(writer.digest())
+}
+
+/// Extracts the `sha-256` digest from a `Content-Digest` or `Repr-Digest` field value.
+///
+/// Returns `Ok(None)` when the dictionary doesn't contain the `sha-256` member. Members with other algorithms and
+/// parameters are ignored.
+///
+/// # Errors
+///
+/// Returns an error when the value isn't a valid dictionary or the `sha-256` member isn't a valid digest.
+pub fn parse(value: &str) -> std::result::Result<Option<Digest>, ParseError> {
+    let mut digest = None;
+    for (key, item) in Dictionary::new(value) {
+        let item = item?;
+        // later members with the same key override earlier ones
+        if key == ALGORITHM {
+            digest = Some(item);
+        }
+    }
+    digest
+        .map(|item| {
+            let bytes = item
+                .strip_prefix(':')
+                .and_then(|item| item.strip_suffix(':'))
+                .ok_or(ParseError::InvalidDigest)?;
+            decode_digest(bytes)
+        })
+        .transpose()
+}
+
+/// Verifies the body against a `Content-Digest` or `Repr-Digest` field value.
+///
+/// # Errors
+///
+/// Returns an error with [`ErrorKind::Unsupported`] when the value has no `sha-256` member and an error with
+/// [`ErrorKind::InvalidData`] when the value is invalid or the digest doesn't match. Any error from reading the body
+/// is returned as is.
+pub fn verify(value: &str, body: impl core::Chksumable) -> Result<()> {
+    let expected = parse(value).map_err(io::Error::from)?;
+    verify_digest(expected, body)
+}
+
+/// Formats the digest as an RFC 3230 `Digest` field value.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::{digest_fields, SHA2_256};
+///
+/// let value = digest_fields::format_legacy(SHA2_256::hash(b"data"));
+/// assert_eq!(
+///     value,
+///     "SHA-256=Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc="
+/// );
+/// ```
+#[must_use]
+pub fn format_legacy(digest: Digest) -> String {
+    format!("SHA-256={}", base64::encode(digest))
+}
+
+/// Extracts the `SHA-256` digest from an RFC 3230 `Digest` field value.
+///
+/// The algorithm names are matched case-insensitively. Returns `Ok(None)` when there is no `SHA-256` instance.
+///
+/// # Errors
+///
+/// Returns an error when the value is malformed or the `SHA-256` instance isn't a valid digest.
+pub fn parse_legacy(value: &str) -> std::result::Result<Option<Digest>, ParseError> {
+    let mut digest = None;
+    for instance in value.split(',') {
+        let (algorithm, encoded) = instance.trim().split_once('=').ok_or(ParseError::InvalidDictionary)?;
+        if algorithm.eq_ignore_ascii_case(ALGORITHM) {
+            digest = Some(decode_digest(encoded)?);
+        }
+    }
+    Ok(digest)
+}
+
+/// Verifies the body against an RFC 3230 `Digest` field value.
+///
+/// # Errors
+///
+/// Returns errors like [`verify`].
+pub fn verify_legacy(value: &str, body: impl core::Chksumable) -> Result<()> {
+    let expected = parse_legacy(value).map_err(io::Error::from)?;
+    verify_digest(expected, body)
+}
+
+/// Verifies the body against the expected digest.
+fn verify_digest(expected: Option<Digest>, body: impl core::Chksumable) -> Result<()> {
+    let Some(expected) = expected else {
+        let error = io::Error::new(ErrorKind::Unsupported, "no sha-256 digest");
+        return Err(error.into());
+    };
+    let digest = core::chksum::<SHA2_256>(body)?;
+    if digest != expected {
+        let message = format!(
+            "digest mismatch, expected `{}`, got `{}`",
+            format(expected),
+            format(digest)
+        );
+        return Err(io::Error::new(ErrorKind::InvalidData, message).into());
+    }
+    Ok(())
+}
+
+/// Decodes a Base64 encoded digest.
+fn decode_digest(encoded: &str) -> std::result::Result<Digest, ParseError> {
+    let bytes = base64::decode(encoded).ok_or(ParseError::InvalidDigest)?;
+    let bytes: [u8; hash::DIGEST_LENGTH_BYTES] = bytes.try_into().map_err(|_| ParseError::InvalidDigest)?;
+    Ok(Digest::new(bytes))
+}
+
+/// An iterator over members of a structured field dictionary as defined by
+/// [RFC 8941](https://www.rfc-editor.org/rfc/rfc8941#section-4.2.2), yielding keys and raw bare items.
+struct Dictionary<'a> {
+    input: &'a str,
+    failed: bool,
+}
+
+impl<'a> Dictionary<'a> {
+    fn new(input: &'a str) -> Self {
+        let input = input.trim_matches([' ', '\t']);
+        Self { input, failed: false }
+    }
+
+    /// Parses a key at the beginning of the input.
+    fn key(&mut self) -> Option<&'a str> {
+        let bytes = self.input.as_bytes();
+        if !bytes
+            .first()
+            .is_some_and(|byte| byte.is_ascii_lowercase() || *byte == b'*')
+        {
+            return None;
+        }
+        let length = bytes
+            .iter()
+            .position(|byte| !(byte.is_ascii_lowercase() || byte.is_ascii_digit() || b"_-.*".contains(byte)))
+            .unwrap_or(bytes.len());
+        let (key, rest) = self.input.split_at(length);
+        self.input = rest;
+        Some(key)
+    }
+
+    /// Parses a bare item at the beginning of the input.
+    fn item(&mut self) -> Option<&'a str> {
+        let bytes = self.input.as_bytes();
+        let length = match bytes.first()? {
+            b':' => bytes[1..].iter().position(|&byte| byte == b':')? + 2,
+            b'"' => {
+                let mut escaped = false;
+                let end = bytes[1..].iter().position(|&byte| {
+                    let end = byte == b'"' && !escaped;
+                    escaped = byte == b'\\' && !escaped;
+                    end
+                })?;
+                end + 2
+            },
+            _ => {
+                bytes
+                    .iter()
+                    .position(|byte| matches!(byte, b',' | b';' | b' ' | b'\t'))
+                    .unwrap_or(bytes.len())
+            },
+        };
+        if length == 0 {
+            return None;
+        }
+        let (item, rest) = self.input.split_at(length);
+        self.input = rest;
+        Some(item)
+    }
+
+    /// Parses a member, skipping its parameters.
+    fn member(&mut self) -> Option<(&'a str, &'a str)> {
+        let key = self.key()?;
+        let item = if let Some(rest) = self.input.strip_prefix('=') {
+            self.input = rest;
+            self.item()?
+        } else {
+            "?1"
+        };
+        while let Some(rest) = self.input.strip_prefix(';') {
+            self.input = rest.trim_start_matches(' ');
+            self.key()?;
+            if let Some(rest) = self.input.strip_prefix('=') {
+                self.input = rest;
+                self.item()?;
+            }
+        }
+
+        self.input = self.input.trim_start_matches([' ', '\t']);
+        if let Some(rest) = self.input.strip_prefix(',') {
+            self.input = rest.trim_start_matches([' ', '\t']);
+            if self.input.is_empty() {
+                // trailing comma is not allowed
+                return None;
+            }
+        } else if !self.input.is_empty() {
+            return None;
+        }
+        Some((key, item))
+    }
+}
+
+impl<'a> Iterator for Dictionary<'a> {
+    type Item = (&'a str, std::result::Result<&'a str, ParseError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.input.is_empty() {
+            return None;
+        }
+        let member = self.member();
+        if member.is_none() {
+            self.failed = true;
+        }
+        Some(member.map_or(("", Err(ParseError::InvalidDictionary)), |(key, item)| (key, Ok(item))))
+    }
+}
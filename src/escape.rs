@@ -0,0 +1,27 @@
+//! Escaping of paths stored in line-based text formats.
+//!
+//! Backslashes and line feeds are escaped as `\\` and `\n`, so any path fits on a single line.
+
+/// Escapes backslashes and line feeds in the path.
+pub(crate) fn escape(path: &str) -> String {
+    path.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Reverts the escaping of backslashes and line feeds, returning `None` for invalid escapes and empty paths.
+pub(crate) fn unescape(value: &str) -> Option<String> {
+    let mut path = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => {
+                match chars.next()? {
+                    '\\' => path.push('\\'),
+                    'n' => path.push('\n'),
+                    _ => return None,
+                }
+            },
+            char => path.push(char),
+        }
+    }
+    (!path.is_empty()).then_some(path)
+}
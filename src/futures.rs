@@ -0,0 +1,387 @@
+//! This module is optional and can be enabled using the `async-runtime-futures` Cargo feature.
+//!
+//! The [`AsyncReader`] and [`AsyncWriter`] allow on-the-fly calculation of the digest while reading or writing the
+//! data through the [`futures-io`](https://docs.rs/futures-io) traits, used by runtimes like `async-std` or `smol`
+//! and by embedded executors. The [`async_chksum`] function calculates the digest of the whole stream.
+//!
+//! Data received as a [`Stream`] of byte chunks, e.g. from gRPC streaming or HTTP client bodies, is hashed with the
+//! [`async_chksum_stream`] function, or passed through the [`DigestStream`] adapter which yields the digest when the
+//! stream completes. The [`async_try_chksum_stream`] function and the [`TryDigestStream`] adapter handle streams of
+//! results.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `async-runtime-futures` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["async-runtime-futures"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features async-runtime-futures
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::futures;
+//!
+//! # use chksum_sha2_256::Result;
+//! # async fn wrapper() -> Result<()> {
+//! let digest = futures::async_chksum(&b"example data"[..]).await?;
+//! assert_eq!(
+//!     digest.to_hex_lowercase(),
+//!     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::poll_fn;
+use std::io;
+use std::pin::{pin, Pin};
+use std::task::{ready, Context, Poll};
+
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use pin_project_lite::pin_project;
+
+use crate::{ChksumOptions, Digest, Result, SHA2_256};
+
+/// Computes the hash of the data read from the given reader.
+///
+/// # Errors
+///
+/// Returns an error when the reader fails.
+pub async fn async_chksum(reader: impl AsyncRead + Unpin) -> Result<Digest> {
+    let mut options = ChksumOptions::new();
+    async_chksum_with(reader, &mut options).await
+}
+
+/// Computes the hash of the data read from the given reader using the buffer from the options.
+///
+/// # Errors
+///
+/// Returns an error when the reader fails.
+pub async fn async_chksum_with(mut reader: impl AsyncRead + Unpin, options: &mut ChksumOptions) -> Result<Digest> {
+    let mut hash = SHA2_256::new();
+    options.futures_update(&mut reader, &mut hash).await?;
+    Ok(hash.digest())
+}
+
+/// Wraps a reader and calculates the hash digest on the fly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsyncReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    inner: R,
+    hash: SHA2_256,
+}
+
+impl<R> AsyncReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Creates new [`AsyncReader`].
+    pub fn new(inner: R) -> Self {
+        Self::with_hash(inner, SHA2_256::new())
+    }
+
+    /// Creates new [`AsyncReader`] with provided hash.
+    #[must_use]
+    pub const fn with_hash(inner: R, hash: SHA2_256) -> Self {
+        Self { inner, hash }
+    }
+
+    /// Unwraps this [`AsyncReader`], returning the underlying reader.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        let Self { inner, .. } = self;
+        inner
+    }
+
+    /// Returns calculated hash digest.
+    #[must_use]
+    pub fn digest(&self) -> Digest {
+        self.hash.digest()
+    }
+}
+
+impl<R> AsyncRead for AsyncReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let Self { inner, hash } = self.get_mut();
+        let length = ready!(Pin::new(inner).poll_read(cx, buf))?;
+        hash.update(&buf[..length]);
+        Poll::Ready(Ok(length))
+    }
+}
+
+/// Wraps a writer and calculates the hash digest on the fly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsyncWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    inner: W,
+    hash: SHA2_256,
+}
+
+impl<W> AsyncWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Creates new [`AsyncWriter`].
+    pub fn new(inner: W) -> Self {
+        Self::with_hash(inner, SHA2_256::new())
+    }
+
+    /// Creates new [`AsyncWriter`] with provided hash.
+    #[must_use]
+    pub const fn with_hash(inner: W, hash: SHA2_256) -> Self {
+        Self { inner, hash }
+    }
+
+    /// Unwraps this [`AsyncWriter`], returning the underlying writer.
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        let Self { inner, .. } = self;
+        inner
+    }
+
+    /// Returns calculated hash digest.
+    #[must_use]
+    pub fn digest(&self) -> Digest {
+        self.hash.digest()
+    }
+}
+
+impl<W> AsyncWrite for AsyncWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let Self { inner, hash } = self.get_mut();
+        let length = ready!(Pin::new(inner).poll_write(cx, buf))?;
+        hash.update(&buf[..length]);
+        Poll::Ready(Ok(length))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+/// Computes the hash of all chunks of the given stream.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::futures;
+///
+/// # async fn wrapper() {
+/// let stream = ::futures::stream::iter([&b"example "[..], b"data"]);
+/// let digest = futures::async_chksum_stream(stream).await;
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # }
+/// ```
+pub async fn async_chksum_stream<S>(stream: S) -> Digest
+where
+    S: Stream,
+    S::Item: AsRef<[u8]>,
+{
+    let mut stream = pin!(stream);
+    let mut hash = SHA2_256::new();
+    while let Some(chunk) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        hash.update(chunk);
+    }
+    hash.digest()
+}
+
+/// Computes the hash of all chunks of the given stream of results.
+///
+/// # Errors
+///
+/// Returns the first error yielded by the stream.
+pub async fn async_try_chksum_stream<S, T, E>(stream: S) -> std::result::Result<Digest, E>
+where
+    S: Stream<Item = std::result::Result<T, E>>,
+    T: AsRef<[u8]>,
+{
+    let mut stream = pin!(stream);
+    let mut hash = SHA2_256::new();
+    while let Some(chunk) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+        hash.update(chunk?);
+    }
+    Ok(hash.digest())
+}
+
+pin_project! {
+    /// Wraps a stream of byte chunks and calculates the hash digest on the fly.
+    ///
+    /// The chunks are passed through unchanged. The digest is available once the inner stream completes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ::futures::StreamExt;
+    /// use chksum_sha2_256::futures::DigestStream;
+    ///
+    /// # async fn wrapper() {
+    /// let mut stream = DigestStream::new(::futures::stream::iter([&b"example "[..], b"data"]));
+    /// while let Some(chunk) = stream.next().await {
+    ///     // process the chunk
+    /// }
+    /// let digest = stream.digest().unwrap();
+    /// assert_eq!(
+    ///     digest.to_hex_lowercase(),
+    ///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+    /// );
+    /// # }
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct DigestStream<S> {
+        #[pin]
+        inner: S,
+        hash: SHA2_256,
+        digest: Option<Digest>,
+    }
+}
+
+impl<S> DigestStream<S> {
+    /// Creates new [`DigestStream`].
+    pub fn new(inner: S) -> Self {
+        Self::with_hash(inner, SHA2_256::new())
+    }
+
+    /// Creates new [`DigestStream`] with provided hash.
+    #[must_use]
+    pub const fn with_hash(inner: S, hash: SHA2_256) -> Self {
+        let digest = None;
+        Self { inner, hash, digest }
+    }
+
+    /// Unwraps this [`DigestStream`], returning the underlying stream.
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        let Self { inner, .. } = self;
+        inner
+    }
+
+    /// Returns the hash digest of all chunks, or `None` if the stream hasn't completed yet.
+    #[must_use]
+    pub const fn digest(&self) -> Option<Digest> {
+        self.digest
+    }
+}
+
+impl<S> Stream for DigestStream<S>
+where
+    S: Stream,
+    S::Item: AsRef<[u8]>,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let chunk = ready!(this.inner.poll_next(cx));
+        match &chunk {
+            Some(chunk) => this.hash.update(chunk),
+            None => {
+                this.digest.get_or_insert_with(|| this.hash.digest());
+            },
+        }
+        Poll::Ready(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pin_project! {
+    /// Wraps a stream of results with byte chunks and calculates the hash digest on the fly.
+    ///
+    /// The results are passed through unchanged. The digest is available once the inner stream completes without
+    /// yielding any error.
+    #[derive(Clone, Debug)]
+    pub struct TryDigestStream<S> {
+        #[pin]
+        inner: S,
+        hash: SHA2_256,
+        digest: Option<Digest>,
+        failed: bool,
+    }
+}
+
+impl<S> TryDigestStream<S> {
+    /// Creates new [`TryDigestStream`].
+    pub fn new(inner: S) -> Self {
+        Self::with_hash(inner, SHA2_256::new())
+    }
+
+    /// Creates new [`TryDigestStream`] with provided hash.
+    #[must_use]
+    pub const fn with_hash(inner: S, hash: SHA2_256) -> Self {
+        let digest = None;
+        let failed = false;
+        Self {
+            inner,
+            hash,
+            digest,
+            failed,
+        }
+    }
+
+    /// Unwraps this [`TryDigestStream`], returning the underlying stream.
+    #[must_use]
+    pub fn into_inner(self) -> S {
+        let Self { inner, .. } = self;
+        inner
+    }
+
+    /// Returns the hash digest of all chunks, or `None` if the stream hasn't completed yet or yielded an error.
+    #[must_use]
+    pub const fn digest(&self) -> Option<Digest> {
+        self.digest
+    }
+}
+
+impl<S, T, E> Stream for TryDigestStream<S>
+where
+    S: Stream<Item = std::result::Result<T, E>>,
+    T: AsRef<[u8]>,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let chunk = ready!(this.inner.poll_next(cx));
+        match &chunk {
+            Some(Ok(chunk)) => this.hash.update(chunk),
+            Some(Err(_)) => *this.failed = true,
+            None if !*this.failed => {
+                this.digest.get_or_insert_with(|| this.hash.digest());
+            },
+            None => {},
+        }
+        Poll::Ready(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
@@ -0,0 +1,306 @@
+//! Object IDs of Git repositories using the SHA-256 object format.
+//!
+//! Git identifies every object by the hash of its type, its length and its content, i.e.
+//! `SHA-256("<type> <length>\0" || content)`. Repositories created with `git init --object-format=sha256` use the
+//! SHA-2 256 hash, so the functions in this module return IDs identical to the ones reported by `git hash-object`
+//! and `git write-tree` in such repositories.
+//!
+//! Use [`blob_id`] and [`blob_id_file`] for file contents, [`tree_id`] for trees built from [`TreeEntry`] values and
+//! [`tree_id_dir`] for trees of directories on disk. Commits and tags are hashed from their raw content with
+//! [`object_id`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::git;
+//!
+//! let digest = git::blob_id(b"hello\n");
+//! assert_eq!(
+//!     digest.to_hex_lowercase(),
+//!     "2cf8d83d9ee29543b34a87727421fdecb7e3f3a183d337639025de576db9ebb4"
+//! );
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use crate::{ChksumOptions, Digest, Result, SHA2_256};
+
+/// The name of the directory with repository metadata, skipped by [`tree_id_dir`].
+const GIT_DIR: &[u8] = b".git";
+
+/// The type of Git object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectType {
+    /// The content of a file.
+    Blob,
+    /// The listing of a directory.
+    Tree,
+    /// The commit pointing to a tree and its parent commits.
+    Commit,
+    /// The annotated tag pointing to another object.
+    Tag,
+}
+
+impl ObjectType {
+    /// Returns the name of the type as used in the object header.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Blob => "blob",
+            Self::Tree => "tree",
+            Self::Commit => "commit",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+impl Display for ObjectType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The mode of a tree entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// The regular file (`100644`).
+    File,
+    /// The executable file (`100755`).
+    Executable,
+    /// The symbolic link (`120000`), whose blob contains the link target.
+    Symlink,
+    /// The subdirectory (`40000`).
+    Directory,
+    /// The submodule (`160000`), pointing to a commit of another repository.
+    Gitlink,
+}
+
+impl Mode {
+    /// Returns the octal representation of the mode as stored in tree objects.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "100644",
+            Self::Executable => "100755",
+            Self::Symlink => "120000",
+            Self::Directory => "40000",
+            Self::Gitlink => "160000",
+        }
+    }
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single entry of a tree object.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeEntry {
+    mode: Mode,
+    name: Vec<u8>,
+    id: Digest,
+}
+
+impl TreeEntry {
+    /// Creates a new entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the name is empty or contains `/` or NUL byte.
+    #[must_use]
+    pub fn new(mode: Mode, name: impl Into<Vec<u8>>, id: Digest) -> Self {
+        let name = name.into();
+        assert!(!name.is_empty(), "entry name must not be empty");
+        assert!(
+            !name.contains(&b'/') && !name.contains(&0),
+            "entry name must not contain slash or NUL byte"
+        );
+        Self { mode, name, id }
+    }
+
+    /// Returns the mode of the entry.
+    #[must_use]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the name of the entry.
+    #[must_use]
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Returns the ID of the object the entry points to.
+    #[must_use]
+    pub const fn id(&self) -> Digest {
+        self.id
+    }
+
+    /// Compares entries the way Git sorts them, i.e. as if directory names ended with `/`.
+    fn cmp_git(&self, other: &Self) -> Ordering {
+        let suffix = |entry: &Self| {
+            if entry.mode == Mode::Directory {
+                Some(b'/')
+            } else {
+                None
+            }
+        };
+        self.name
+            .iter()
+            .copied()
+            .chain(suffix(self))
+            .cmp(other.name.iter().copied().chain(suffix(other)))
+    }
+}
+
+/// Creates a hash with the header of the object already processed.
+fn hasher(kind: ObjectType, length: u64) -> SHA2_256 {
+    let mut hash = SHA2_256::new();
+    hash.update(format!("{kind} {length}\0"));
+    hash
+}
+
+/// Computes the ID of the object with the given type and raw content.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::git::{self, ObjectType};
+///
+/// let digest = git::object_id(ObjectType::Blob, b"hello\n");
+/// assert_eq!(digest, git::blob_id(b"hello\n"));
+/// ```
+#[must_use]
+pub fn object_id(kind: ObjectType, content: impl AsRef<[u8]>) -> Digest {
+    let content = content.as_ref();
+    let mut hash = hasher(kind, content.len() as u64);
+    hash.update(content);
+    hash.digest()
+}
+
+/// Computes the ID of the blob with the given content.
+#[must_use]
+pub fn blob_id(content: impl AsRef<[u8]>) -> Digest {
+    object_id(ObjectType::Blob, content)
+}
+
+/// Computes the ID of the blob with the content of the given file.
+///
+/// The file is streamed, so it doesn't have to fit into memory.
+///
+/// # Errors
+///
+/// Returns an error when the file cannot be read or its length changes while it is hashed.
+pub fn blob_id_file(path: impl AsRef<Path>) -> Result<Digest> {
+    let file = File::open(path)?;
+    let length = file.metadata()?.len();
+    let mut hash = hasher(ObjectType::Blob, length);
+    let read = ChksumOptions::new().update(file, &mut hash)?;
+    if read != length {
+        let error = io::Error::new(ErrorKind::UnexpectedEof, "file length changed during hashing");
+        return Err(error.into());
+    }
+    Ok(hash.digest())
+}
+
+/// Computes the ID of the tree with the given entries.
+///
+/// The entries don't have to be sorted, they are ordered the way Git expects.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::git::{self, Mode, TreeEntry};
+///
+/// let entries = [TreeEntry::new(
+///     Mode::File,
+///     "hello.txt",
+///     git::blob_id(b"hello\n"),
+/// )];
+/// let digest = git::tree_id(&entries);
+/// ```
+#[must_use]
+pub fn tree_id(entries: &[TreeEntry]) -> Digest {
+    let mut entries: Vec<&TreeEntry> = entries.iter().collect();
+    entries.sort_by(|left, right| left.cmp_git(right));
+
+    let mut content = Vec::new();
+    for entry in entries {
+        content.extend_from_slice(entry.mode.as_str().as_bytes());
+        content.push(b' ');
+        content.extend_from_slice(&entry.name);
+        content.push(0);
+        content.extend_from_slice(entry.id.as_bytes());
+    }
+    object_id(ObjectType::Tree, content)
+}
+
+/// Computes the ID of the tree with the content of the given directory, as `git add --all && git write-tree` would.
+///
+/// The `.git` entries are skipped and so are empty subdirectories, since Git doesn't track them. Files with the
+/// owner execute permission are stored as [`Mode::Executable`] on Unix platforms. Entries other than regular files,
+/// symbolic links and directories are skipped. Ignore rules like `.gitignore` files are not taken into account.
+///
+/// # Errors
+///
+/// Returns an error when the directory or any of its entries cannot be read.
+pub fn tree_id_dir(path: impl AsRef<Path>) -> Result<Digest> {
+    let entries = dir_entries(path.as_ref())?;
+    Ok(tree_id(&entries))
+}
+
+/// Collects the tree entries of the given directory.
+fn dir_entries(path: &Path) -> Result<Vec<TreeEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let name = entry.file_name().as_encoded_bytes().to_vec();
+        if name == GIT_DIR {
+            continue;
+        }
+
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        let (mode, id) = if file_type.is_symlink() {
+            let target = fs::read_link(&path)?;
+            (Mode::Symlink, blob_id(target.as_os_str().as_encoded_bytes()))
+        } else if file_type.is_dir() {
+            let entries = dir_entries(&path)?;
+            if entries.is_empty() {
+                continue;
+            }
+            (Mode::Directory, tree_id(&entries))
+        } else if file_type.is_file() {
+            let mode = if is_executable(&entry.metadata()?) {
+                Mode::Executable
+            } else {
+                Mode::File
+            };
+            (mode, blob_id_file(&path)?)
+        } else {
+            continue;
+        };
+        entries.push(TreeEntry::new(mode, name, id));
+    }
+    Ok(entries)
+}
+
+/// Checks whether the owner execute permission is set.
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o100 != 0
+}
+
+/// Checks whether the owner execute permission is set.
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
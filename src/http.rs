@@ -0,0 +1,555 @@
+//! This module is optional and can be enabled using the `http` Cargo feature.
+//!
+//! The [`ContentDigestLayer`] is a [`tower`](https://docs.rs/tower) middleware adding integrity fields as defined by
+//! [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530) to HTTP messages without buffering whole bodies:
+//!
+//! * response bodies are hashed while they are streamed and the `Content-Digest` field is sent as a trailer,
+//!   alternatively responses can be buffered to send the field as a header, see [`ResponseMode`],
+//! * request bodies with the `Content-Digest` header are hashed while they are read and fail with [`VerifyError`] at
+//!   the end of the stream when the digest doesn't match. The inner service has already been called at that point,
+//!   so it must read the whole body before acting on the request to never process corrupted data. Requests with a
+//!   malformed `Content-Digest` header are answered with `400 Bad Request` without calling the inner service.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `http` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["http"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features http
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::convert::Infallible;
+//!
+//! use chksum_sha2_256::http::{ContentDigestLayer, ResponseMode};
+//! use http::{Request, Response};
+//! use http_body_util::Full;
+//! use tower::{service_fn, ServiceBuilder, ServiceExt};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Infallible> {
+//! let service = ServiceBuilder::new()
+//!     .layer(ContentDigestLayer::new().with_response_mode(ResponseMode::Buffer))
+//!     .service(service_fn(|_request| {
+//!         async { Ok::<_, Infallible>(Response::new(Full::new(&b"data"[..]))) }
+//!     }));
+//!
+//! let response = service.oneshot(Request::new(Full::new(&b""[..]))).await?;
+//! assert_eq!(
+//!     response.headers()["content-digest"],
+//!     "sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use ::http::header::{CONTENT_LENGTH, CONTENT_TYPE, TRAILER};
+use ::http::response::Parts;
+use ::http::{HeaderMap, HeaderName, HeaderValue, Request, Response, StatusCode};
+use bytes::{Buf, Bytes, BytesMut};
+use http_body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::digest_fields::{self, ParseError};
+use crate::{Digest, SHA2_256};
+
+/// The boxed error returned by bodies.
+pub type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// The name of the `Content-Digest` field.
+pub const CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// An error of requests which fail the verification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The `Content-Digest` field is malformed, the request is answered with `400 Bad Request` describing the error.
+    InvalidField(ParseError),
+    /// The digest of the body doesn't match the `Content-Digest` field, the request body fails at its end.
+    Mismatch {
+        /// The digest from the field.
+        expected: Digest,
+        /// The digest of the body.
+        actual: Digest,
+    },
+}
+
+impl Display for VerifyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidField(error) => write!(f, "invalid `Content-Digest` field: {error}"),
+            Self::Mismatch { expected, actual } => {
+                write!(f, "content digest mismatch, expected `{expected}`, got `{actual}`")
+            },
+        }
+    }
+}
+
+impl StdError for VerifyError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::InvalidField(error) => Some(error),
+            Self::Mismatch { .. } => None,
+        }
+    }
+}
+
+/// The way the `Content-Digest` field is added to responses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResponseMode {
+    /// The body is streamed and the field is sent as a trailer announced by the `Trailer` header.
+    ///
+    /// Note that trailers are dropped by HTTP/1.1 servers unless the response uses chunked encoding and the client
+    /// accepts them.
+    #[default]
+    Trailer,
+    /// The body is buffered and the field is sent as a header.
+    Buffer,
+}
+
+/// A layer applying the [`ContentDigest`] middleware.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentDigestLayer {
+    response_mode: ResponseMode,
+    verify_requests: bool,
+}
+
+impl ContentDigestLayer {
+    /// Creates a new layer sending digests as trailers and verifying requests.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            response_mode: ResponseMode::Trailer,
+            verify_requests: true,
+        }
+    }
+
+    /// Sets the way the `Content-Digest` field is added to responses.
+    #[must_use]
+    pub const fn with_response_mode(mut self, response_mode: ResponseMode) -> Self {
+        self.response_mode = response_mode;
+        self
+    }
+
+    /// Sets whether request bodies are verified against their `Content-Digest` header.
+    #[must_use]
+    pub const fn with_request_verification(mut self, verify_requests: bool) -> Self {
+        self.verify_requests = verify_requests;
+        self
+    }
+}
+
+impl Default for ContentDigestLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for ContentDigestLayer {
+    type Service = ContentDigest<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ContentDigest {
+            inner,
+            response_mode: self.response_mode,
+            verify_requests: self.verify_requests,
+        }
+    }
+}
+
+/// A middleware adding the `Content-Digest` field to responses and verifying request bodies.
+#[derive(Clone, Debug)]
+pub struct ContentDigest<S> {
+    inner: S,
+    response_mode: ResponseMode,
+    verify_requests: bool,
+}
+
+impl<S> ContentDigest<S> {
+    /// Creates a new middleware with the default configuration of [`ContentDigestLayer`].
+    pub const fn new(inner: S) -> Self {
+        let ContentDigestLayer {
+            response_mode,
+            verify_requests,
+        } = ContentDigestLayer::new();
+        Self {
+            inner,
+            response_mode,
+            verify_requests,
+        }
+    }
+
+    /// Returns a reference to the inner service.
+    pub const fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwraps this middleware, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ContentDigest<S>
+where
+    S: Service<Request<DigestBody<ReqBody>>, Response = Response<ResBody>>,
+    ReqBody: Body,
+    ReqBody::Error: Into<BoxError>,
+    ResBody: Body,
+    ResBody::Error: Into<BoxError>,
+{
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future, ResBody>;
+    type Response = Response<DigestBody<ResBody>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let action = if self.verify_requests {
+            match expected_digest(request.headers()) {
+                Ok(Some(expected)) => Action::Verify(expected),
+                Ok(None) => Action::Pass,
+                Err(error) => {
+                    let response = Some(bad_request(VerifyError::InvalidField(error)));
+                    return ResponseFuture {
+                        state: State::Rejected { response },
+                        response_mode: self.response_mode,
+                    };
+                },
+            }
+        } else {
+            Action::Pass
+        };
+        let request = request.map(|body| DigestBody::stream(body, action));
+        let future = self.inner.call(request);
+        ResponseFuture {
+            state: State::Pending { future },
+            response_mode: self.response_mode,
+        }
+    }
+}
+
+/// Extracts the expected digest from all `Content-Digest` headers.
+fn expected_digest(headers: &HeaderMap) -> Result<Option<Digest>, ParseError> {
+    let mut values = Vec::new();
+    for value in headers.get_all(CONTENT_DIGEST) {
+        values.push(value.to_str().map_err(|_| ParseError::InvalidDictionary)?);
+    }
+    digest_fields::parse(&values.join(", "))
+}
+
+/// Returns the `400 Bad Request` response describing the error.
+fn bad_request<B>(error: VerifyError) -> Response<DigestBody<B>> {
+    let body = Bytes::from(error.to_string());
+    let mut response = Response::new(DigestBody::full(body, None));
+    *response.status_mut() = StatusCode::BAD_REQUEST;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"));
+    response
+}
+
+/// Returns the `Content-Digest` field value of the digest.
+fn field_value(digest: Digest) -> HeaderValue {
+    HeaderValue::try_from(digest_fields::format(digest)).expect("field value should contain only visible characters")
+}
+
+pin_project! {
+    /// A future returned by the [`ContentDigest`] middleware.
+    pub struct ResponseFuture<F, B> {
+        #[pin]
+        state: State<F, B>,
+        response_mode: ResponseMode,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<F, B> {
+        Pending {
+            #[pin]
+            future: F,
+        },
+        Buffering {
+            parts: Option<Parts>,
+            #[pin]
+            body: B,
+            hash: SHA2_256,
+            buffer: BytesMut,
+            trailers: Option<HeaderMap>,
+        },
+        Rejected {
+            response: Option<Response<DigestBody<B>>>,
+        },
+        Done,
+    }
+}
+
+impl<F, B, E> Future for ResponseFuture<F, B>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body,
+    B::Error: Into<BoxError>,
+{
+    type Output = Result<Response<DigestBody<B>>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            match this.state.as_mut().project() {
+                StateProj::Pending { future } => {
+                    let (mut parts, body) = ready!(future.poll(cx))?.into_parts();
+                    match this.response_mode {
+                        ResponseMode::Trailer => {
+                            parts
+                                .headers
+                                .append(TRAILER, HeaderValue::from_static("content-digest"));
+                            this.state.set(State::Done);
+                            let body = DigestBody::stream(body, Action::Trailer);
+                            return Poll::Ready(Ok(Response::from_parts(parts, body)));
+                        },
+                        ResponseMode::Buffer => {
+                            this.state.set(State::Buffering {
+                                parts: Some(parts),
+                                body,
+                                hash: SHA2_256::new(),
+                                buffer: BytesMut::new(),
+                                trailers: None,
+                            })
+                        },
+                    }
+                },
+                StateProj::Buffering {
+                    parts,
+                    body,
+                    hash,
+                    buffer,
+                    trailers,
+                } => {
+                    let frame = ready!(body.poll_frame(cx));
+                    let mut parts = match frame {
+                        Some(Ok(frame)) => {
+                            match frame.into_data() {
+                                Ok(mut data) => {
+                                    while data.has_remaining() {
+                                        let chunk = data.chunk();
+                                        hash.update(chunk);
+                                        buffer.extend_from_slice(chunk);
+                                        let length = chunk.len();
+                                        data.advance(length);
+                                    }
+                                },
+                                Err(frame) => {
+                                    if let Ok(frame) = frame.into_trailers() {
+                                        trailers.get_or_insert_with(HeaderMap::new).extend(frame);
+                                    }
+                                },
+                            }
+                            continue;
+                        },
+                        Some(Err(error)) => {
+                            let parts = parts.take().expect("parts should be present until the body ends");
+                            this.state.set(State::Done);
+                            let body = DigestBody::failed(error.into());
+                            return Poll::Ready(Ok(Response::from_parts(parts, body)));
+                        },
+                        None => parts.take().expect("parts should be present until the body ends"),
+                    };
+                    parts.headers.insert(CONTENT_DIGEST, field_value(hash.digest()));
+                    parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(buffer.len()));
+                    let body = DigestBody::full(buffer.split().freeze(), trailers.take());
+                    this.state.set(State::Done);
+                    return Poll::Ready(Ok(Response::from_parts(parts, body)));
+                },
+                StateProj::Rejected { response } => {
+                    let response = response.take().expect("response should be present until returned");
+                    this.state.set(State::Done);
+                    return Poll::Ready(Ok(response));
+                },
+                StateProj::Done => panic!("future polled after completion"),
+            }
+        }
+    }
+}
+
+/// The action performed when the streamed body ends.
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    /// Nothing is done.
+    Pass,
+    /// The digest is sent as a trailer.
+    Trailer,
+    /// The digest is compared with the expected one.
+    Verify(Digest),
+}
+
+pin_project! {
+    /// A body of requests and responses processed by the [`ContentDigest`] middleware.
+    pub struct DigestBody<B> {
+        #[pin]
+        kind: Kind<B>,
+    }
+}
+
+pin_project! {
+    #[project = KindProj]
+    enum Kind<B> {
+        Stream {
+            #[pin]
+            inner: B,
+            hash: SHA2_256,
+            action: Action,
+            finished: bool,
+        },
+        Full {
+            data: Option<Bytes>,
+            trailers: Option<HeaderMap>,
+        },
+        Failed {
+            error: Option<BoxError>,
+        },
+    }
+}
+
+impl<B> DigestBody<B> {
+    fn stream(inner: B, action: Action) -> Self {
+        let hash = SHA2_256::new();
+        let finished = false;
+        let kind = Kind::Stream {
+            inner,
+            hash,
+            action,
+            finished,
+        };
+        Self { kind }
+    }
+
+    fn full(data: Bytes, trailers: Option<HeaderMap>) -> Self {
+        let data = Some(data).filter(|data| !data.is_empty());
+        let kind = Kind::Full { data, trailers };
+        Self { kind }
+    }
+
+    fn failed(error: BoxError) -> Self {
+        let error = Some(error);
+        let kind = Kind::Failed { error };
+        Self { kind }
+    }
+}
+
+impl<B> Body for DigestBody<B>
+where
+    B: Body,
+    B::Error: Into<BoxError>,
+{
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match self.project().kind.project() {
+            KindProj::Stream {
+                inner,
+                hash,
+                action,
+                finished,
+            } => {
+                if *finished {
+                    return Poll::Ready(None);
+                }
+                let frame = match ready!(inner.poll_frame(cx)) {
+                    Some(Ok(frame)) => frame,
+                    Some(Err(error)) => return Poll::Ready(Some(Err(error.into()))),
+                    None => {
+                        *finished = true;
+                        return Poll::Ready(match *action {
+                            Action::Pass => None,
+                            Action::Trailer => {
+                                let mut trailers = HeaderMap::new();
+                                trailers.insert(CONTENT_DIGEST, field_value(hash.digest()));
+                                Some(Ok(Frame::trailers(trailers)))
+                            },
+                            Action::Verify(expected) => verify(expected, hash.digest()).err().map(Err),
+                        });
+                    },
+                };
+                let frame = match frame.into_data() {
+                    Ok(mut data) => {
+                        let data = data.copy_to_bytes(data.remaining());
+                        if !matches!(action, Action::Pass) {
+                            hash.update(&data);
+                        }
+                        Frame::data(data)
+                    },
+                    Err(frame) => {
+                        // trailers end the body
+                        *finished = true;
+                        let mut trailers = frame.into_trailers().unwrap_or_default();
+                        match *action {
+                            Action::Pass => {},
+                            Action::Trailer => {
+                                trailers.insert(CONTENT_DIGEST, field_value(hash.digest()));
+                            },
+                            Action::Verify(expected) => {
+                                if let Err(error) = verify(expected, hash.digest()) {
+                                    return Poll::Ready(Some(Err(error)));
+                                }
+                            },
+                        }
+                        Frame::trailers(trailers)
+                    },
+                };
+                Poll::Ready(Some(Ok(frame)))
+            },
+            KindProj::Full { data, trailers } => {
+                if let Some(data) = data.take() {
+                    return Poll::Ready(Some(Ok(Frame::data(data))));
+                }
+                Poll::Ready(trailers.take().map(|trailers| Ok(Frame::trailers(trailers))))
+            },
+            KindProj::Failed { error } => Poll::Ready(error.take().map(Err)),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        match &self.kind {
+            Kind::Stream { finished, .. } => *finished,
+            Kind::Full { data, trailers } => data.is_none() && trailers.is_none(),
+            Kind::Failed { error } => error.is_none(),
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.kind {
+            Kind::Stream { inner, .. } => inner.size_hint(),
+            Kind::Full { data, .. } => SizeHint::with_exact(data.as_ref().map_or(0, Bytes::len) as u64),
+            Kind::Failed { .. } => SizeHint::default(),
+        }
+    }
+}
+
+/// Compares the digest of the body with the expected one.
+fn verify(expected: Digest, actual: Digest) -> Result<(), BoxError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(VerifyError::Mismatch { expected, actual }.into())
+    }
+}
@@ -0,0 +1,250 @@
+//! A minimal JSON reader and writer for the documents produced by this crate.
+//!
+//! Numbers are kept as written in the document, the consumers convert them into the types they expect.
+
+use std::fmt::Write as _;
+
+/// A parsed JSON value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Object),
+}
+
+/// A parsed JSON object with its members in the document order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Object {
+    /// The line of the opening brace, counting from 1.
+    pub(crate) line: usize,
+    pub(crate) members: Vec<(String, Value)>,
+}
+
+impl Object {
+    /// Returns the value of the member with the given name.
+    pub(crate) fn get(&self, name: &str) -> Option<&Value> {
+        self.members
+            .iter()
+            .find_map(|(member, value)| (member == name).then_some(value))
+    }
+}
+
+/// Returns the string as a JSON string literal.
+pub(crate) fn string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            char if char.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(char));
+            },
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Parses the JSON document, returning the line of the first error, counting from 1.
+pub(crate) fn parse(document: &str) -> Result<Value, usize> {
+    let mut parser = Parser {
+        document: document.as_bytes(),
+        position: 0,
+    };
+    let value = parser.value(0).ok_or_else(|| parser.line())?;
+    parser.whitespace();
+    if parser.position < parser.document.len() {
+        return Err(parser.line());
+    }
+    Ok(value)
+}
+
+/// The maximum nesting of arrays and objects, which bounds the recursion.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    document: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the line of the current position.
+    fn line(&self) -> usize {
+        let end = self.position.min(self.document.len());
+        self.document[..end].iter().filter(|&&byte| byte == b'\n').count() + 1
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.document.get(self.position).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes the given byte after optional whitespace.
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.whitespace();
+        (self.peek()? == byte).then(|| self.position += 1)
+    }
+
+    fn literal(&mut self, literal: &[u8], value: Value) -> Option<Value> {
+        let end = self.position + literal.len();
+        (self.document.get(self.position..end)? == literal).then(|| {
+            self.position = end;
+            value
+        })
+    }
+
+    fn value(&mut self, depth: usize) -> Option<Value> {
+        self.whitespace();
+        match self.peek()? {
+            b'n' => self.literal(b"null", Value::Null),
+            b't' => self.literal(b"true", Value::Bool(true)),
+            b'f' => self.literal(b"false", Value::Bool(false)),
+            b'"' => self.string().map(Value::String),
+            b'[' if depth < MAX_DEPTH => self.array(depth + 1),
+            b'{' if depth < MAX_DEPTH => self.object(depth + 1).map(Value::Object),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => None,
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Option<Value> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.expect(b']').is_some() {
+            return Some(Value::Array(values));
+        }
+        loop {
+            values.push(self.value(depth)?);
+            if self.expect(b']').is_some() {
+                return Some(Value::Array(values));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Option<Object> {
+        self.expect(b'{')?;
+        let line = self.line();
+        let mut members = Vec::new();
+        if self.expect(b'}').is_some() {
+            return Some(Object { line, members });
+        }
+        loop {
+            self.whitespace();
+            let name = self.string()?;
+            self.expect(b':')?;
+            members.push((name, self.value(depth)?));
+            if self.expect(b'}').is_some() {
+                return Some(Object { line, members });
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.peek()? {
+            b'0' => self.position += 1,
+            b'1'..=b'9' => self.digits(),
+            _ => return None,
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.peek().filter(u8::is_ascii_digit)?;
+            self.digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            self.peek().filter(u8::is_ascii_digit)?;
+            self.digits();
+        }
+        let number = std::str::from_utf8(&self.document[start..self.position]).ok()?;
+        Some(Value::Number(number.to_owned()))
+    }
+
+    fn digits(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.position += 1;
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.position += 1;
+        let mut bytes = Vec::new();
+        loop {
+            let byte = self.peek()?;
+            self.position += 1;
+            match byte {
+                b'"' => return String::from_utf8(bytes).ok(),
+                b'\\' => {
+                    let escaped = self.peek()?;
+                    self.position += 1;
+                    let char = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode()?,
+                        _ => return None,
+                    };
+                    bytes.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
+                },
+                0x00..=0x1F => return None,
+                byte => bytes.push(byte),
+            }
+        }
+    }
+
+    /// Parses the hexadecimal digits of a `\u` escape, including a following low surrogate.
+    fn unicode(&mut self) -> Option<char> {
+        let high = self.hex()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if self.document.get(self.position..self.position + 2)? != b"\\u" {
+            return None;
+        }
+        self.position += 2;
+        let low = self.hex()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return None;
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+    }
+
+    fn hex(&mut self) -> Option<u32> {
+        let digits = self.document.get(self.position..self.position + 4)?;
+        let digits = std::str::from_utf8(digits).ok()?;
+        if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.position += 4;
+        u32::from_str_radix(digits, 16).ok()
+    }
+}
@@ -0,0 +1,172 @@
+//! Multi-buffer hashing of many independent messages.
+//!
+//! Messages are grouped by length and processed in [`LANES`] interleaved lanes, so a single pass of the compression
+//! function advances several messages at once. The lane layout allows the compiler to vectorize the arithmetic.
+
+#[cfg(feature = "hw-accel")]
+use crate::backend::{self, Backend};
+use crate::constants::{H, K};
+use crate::{hash, Digest, SHA2_256};
+
+/// The number of messages processed at once.
+const LANES: usize = 8;
+
+/// The length of a single block in bytes.
+const BLOCK_LENGTH_BYTES: usize = hash::BLOCK_LENGTH_BYTES;
+
+/// Computes digests of all the messages.
+pub(crate) fn hash_many<T>(messages: &[T]) -> Vec<Digest>
+where
+    T: AsRef<[u8]>,
+{
+    #[cfg(feature = "hw-accel")]
+    if backend::active() != Backend::Portable {
+        // a single accelerated lane is faster than several portable ones
+        return messages.iter().map(SHA2_256::hash).collect();
+    }
+
+    // messages of similar length share the same number of blocks, so fewer lanes idle
+    let mut order: Vec<usize> = (0..messages.len()).collect();
+    order.sort_by_key(|&index| messages[index].as_ref().len());
+
+    let mut digests = vec![[0u8; hash::DIGEST_LENGTH_BYTES]; messages.len()];
+    for indices in order.chunks(LANES) {
+        if let [index] = indices {
+            digests[*index] = SHA2_256::hash(&messages[*index]).into_inner();
+            continue;
+        }
+
+        let group: Vec<&[u8]> = indices.iter().map(|&index| messages[index].as_ref()).collect();
+        for (&index, digest) in indices.iter().zip(hash_lanes(&group)) {
+            digests[index] = digest;
+        }
+    }
+    digests.into_iter().map(Digest::new).collect()
+}
+
+/// Computes digests of up to [`LANES`] messages in parallel.
+fn hash_lanes(messages: &[&[u8]]) -> Vec<[u8; hash::DIGEST_LENGTH_BYTES]> {
+    debug_assert!(
+        messages.len() <= LANES,
+        "number of messages must not exceed number of lanes"
+    );
+
+    let blocks: Vec<usize> = messages.iter().map(|message| blocks_count(message.len())).collect();
+    let rounds = blocks.iter().copied().max().unwrap_or_default();
+
+    let mut state = [[0u32; LANES]; 8];
+    for (word, value) in state.iter_mut().zip(H) {
+        *word = [value; LANES];
+    }
+
+    let mut block = [0u8; BLOCK_LENGTH_BYTES];
+    for round in 0..rounds {
+        let mut w = [[0u32; LANES]; 16];
+        for (lane, message) in messages.iter().enumerate() {
+            if round < blocks[lane] {
+                fill_block(&mut block, message, round);
+                for (word, chunk) in w.iter_mut().zip(block.chunks_exact(4)) {
+                    word[lane] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                }
+            }
+        }
+
+        let updated = compress(&state, &w);
+        for (lane, &count) in blocks.iter().enumerate() {
+            // lanes without any block left keep the final state
+            if round < count {
+                for (word, updated) in state.iter_mut().zip(&updated) {
+                    word[lane] = updated[lane];
+                }
+            }
+        }
+    }
+
+    (0..messages.len())
+        .map(|lane| {
+            let mut digest = [0u8; hash::DIGEST_LENGTH_BYTES];
+            for (chunk, word) in digest.chunks_exact_mut(4).zip(&state) {
+                chunk.copy_from_slice(&word[lane].to_be_bytes());
+            }
+            digest
+        })
+        .collect()
+}
+
+/// Returns the number of blocks of the padded message.
+const fn blocks_count(length: usize) -> usize {
+    // the message is followed by a single `0x80` byte and 8 bytes of the length in bits
+    (length + 1 + 8).div_ceil(BLOCK_LENGTH_BYTES)
+}
+
+/// Fills the buffer with the given block of the padded message.
+fn fill_block(block: &mut [u8; BLOCK_LENGTH_BYTES], message: &[u8], index: usize) {
+    let offset = index * BLOCK_LENGTH_BYTES;
+    block.fill(0);
+
+    let data = message.get(offset..).unwrap_or_default();
+    let data = &data[..data.len().min(BLOCK_LENGTH_BYTES)];
+    block[..data.len()].copy_from_slice(data);
+
+    if offset + data.len() == message.len() && data.len() < BLOCK_LENGTH_BYTES {
+        block[data.len()] = 0x80;
+    }
+    if index + 1 == blocks_count(message.len()) {
+        let length = (message.len() as u64).wrapping_mul(8); // convert byte-length into bits-length
+        block[BLOCK_LENGTH_BYTES - 8..].copy_from_slice(&length.to_be_bytes());
+    }
+}
+
+/// Processes a single block in every lane.
+#[allow(clippy::needless_range_loop)] // lane-wise indexing keeps the loops vectorizable
+fn compress(state: &[[u32; LANES]; 8], block: &[[u32; LANES]; 16]) -> [[u32; LANES]; 8] {
+    let mut w = [[0u32; LANES]; 64];
+    w[..16].copy_from_slice(block);
+    for i in 16..64 {
+        for lane in 0..LANES {
+            let (w2, w7, w15, w16) = (w[i - 2][lane], w[i - 7][lane], w[i - 15][lane], w[i - 16][lane]);
+            let s0 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+            let s1 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+            w[i][lane] = w16.wrapping_add(s0).wrapping_add(w7).wrapping_add(s1);
+        }
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let mut temp1 = [0u32; LANES];
+        let mut temp2 = [0u32; LANES];
+        for lane in 0..LANES {
+            let s1 = e[lane].rotate_right(6) ^ e[lane].rotate_right(11) ^ e[lane].rotate_right(25);
+            let ch = (e[lane] & f[lane]) ^ (!e[lane] & g[lane]);
+            temp1[lane] = h[lane]
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i][lane]);
+            let s0 = a[lane].rotate_right(2) ^ a[lane].rotate_right(13) ^ a[lane].rotate_right(22);
+            let maj = (a[lane] & b[lane]) ^ (a[lane] & c[lane]) ^ (b[lane] & c[lane]);
+            temp2[lane] = s0.wrapping_add(maj);
+        }
+
+        h = g;
+        g = f;
+        f = e;
+        for lane in 0..LANES {
+            e[lane] = d[lane].wrapping_add(temp1[lane]);
+        }
+        d = c;
+        c = b;
+        b = a;
+        for lane in 0..LANES {
+            a[lane] = temp1[lane].wrapping_add(temp2[lane]);
+        }
+    }
+
+    let mut updated = *state;
+    for (word, value) in updated.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        for lane in 0..LANES {
+            word[lane] = word[lane].wrapping_add(value[lane]);
+        }
+    }
+    updated
+}
@@ -239,6 +239,13 @@
 //!
 //! * `reader` enables the [`reader`] module with the [`Reader`] struct.
 //! * `writer` enables the [`writer`] module with the [`Writer`] struct.
+//! * `mmap` enables the [`mmap`] module with the [`chksum_mmap`] function.
+//! * `sparse` enables the [`sparse`] module with the [`chksum_sparse`] function skipping holes of sparse files.
+//! * `hw-accel` enables the [`backend`] module with runtime selection of hardware-accelerated compression function.
+//! * `bytes` enables hashing of [`Buf`](bytes::Buf) implementations with the [`hash_buf`] function.
+//! * `http` enables the [`http`] module with the `tower` middleware adding and verifying the `Content-Digest` field.
+//! * `cas` enables the [`cas`] module with the on-disk content-addressable [`Store`](cas::Store).
+//! * `archive` enables the [`archive`] module hashing tar and zip archives by their logical content.
 //!
 //! By default, neither of these features is enabled.
 //!
@@ -258,6 +265,8 @@
 //! ## Asynchronous Runtime
 //!
 //! * `async-runtime-tokio`: Enables async interface for Tokio runtime.
+//! * `async-runtime-futures`: Enables the [`futures`] module with async interface for `futures-io` traits, e.g. for
+//!   `async-std` or `smol` runtimes.
 //!
 //! By default, neither of these features is enabled.
 //!
@@ -266,14 +275,55 @@
 //! This crate is licensed under the MIT License.
 
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
-#![forbid(unsafe_code)]
+#![cfg_attr(
+    not(any(feature = "hw-accel", feature = "mmap", feature = "sparse")),
+    forbid(unsafe_code)
+)]
+#![cfg_attr(any(feature = "hw-accel", feature = "mmap", feature = "sparse"), deny(unsafe_code))]
 
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "hw-accel")]
+pub mod backend;
+mod base64;
+pub mod cache;
+#[cfg(feature = "cas")]
+pub mod cas;
+pub mod cdc;
+pub mod chunks;
+mod constants;
+pub mod digest_fields;
+mod escape;
+#[cfg(feature = "async-runtime-futures")]
+pub mod futures;
+pub mod git;
+#[cfg(feature = "http")]
+pub mod http;
+mod json;
+mod lanes;
+pub mod manifest;
+pub mod merkle;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod oci;
+pub mod options;
 #[cfg(feature = "reader")]
 pub mod reader;
+pub mod s3;
+pub mod sha256d;
+#[cfg(feature = "sparse")]
+pub mod sparse;
+pub mod tagged;
+mod temporary;
+#[cfg(any(feature = "cas", feature = "reader"))]
+mod verifying;
 #[cfg(feature = "writer")]
 pub mod writer;
 
+use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter, LowerHex, UpperHex};
+use std::hash::{Hash as StdHash, Hasher};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
 
 use chksum_core as core;
 #[cfg(feature = "async-runtime-tokio")]
@@ -284,12 +334,25 @@ pub use chksum_core::{Chksumable, Error, Hash, Hashable, Result};
 #[doc(no_inline)]
 pub use chksum_hash_sha2_256 as hash;
 
+#[cfg(feature = "mmap")]
+#[doc(inline)]
+pub use crate::mmap::chksum_mmap;
+#[cfg(feature = "async-runtime-tokio")]
+#[doc(inline)]
+pub use crate::options::AsyncChksumableWithOptions;
+#[doc(inline)]
+pub use crate::options::{ChksumOptions, ChksumableWithOptions};
 #[cfg(all(feature = "reader", feature = "async-runtime-tokio"))]
 #[doc(inline)]
 pub use crate::reader::AsyncReader;
 #[cfg(feature = "reader")]
 #[doc(inline)]
 pub use crate::reader::Reader;
+#[cfg(feature = "sparse")]
+#[doc(inline)]
+pub use crate::sparse::chksum_sparse;
+#[doc(inline)]
+pub use crate::tagged::TaggedHash;
 #[cfg(all(feature = "writer", feature = "async-runtime-tokio"))]
 #[doc(inline)]
 pub use crate::writer::AsyncWriter;
@@ -355,6 +418,60 @@ pub fn hash(data: impl core::Hashable) -> Digest {
     core::hash::<SHA2_256>(data)
 }
 
+/// Computes the hashes of many independent inputs at once.
+///
+/// The digests are identical to the ones returned by [`hash`] for each input, in the same order.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256 as sha2_256;
+///
+/// let data = [&b"example data"[..], b"", b"example data"];
+/// let digests = sha2_256::hash_many(&data);
+/// assert_eq!(
+///     digests[0].to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// assert_eq!(
+///     digests[1].to_hex_lowercase(),
+///     "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+/// );
+/// ```
+#[must_use]
+pub fn hash_many<T>(data: &[T]) -> Vec<Digest>
+where
+    T: AsRef<[u8]>,
+{
+    SHA2_256::hash_many(data)
+}
+
+/// Computes the hash of the given buffer, e.g. [`Bytes`](bytes::Bytes) or chained buffers.
+///
+/// The buffer is consumed chunk by chunk without copying the data into a contiguous slice. Pass a clone of
+/// [`Bytes`](bytes::Bytes) or a mutable reference to keep the original buffer intact.
+///
+/// # Example
+///
+/// ```rust
+/// use bytes::{Buf, Bytes};
+/// use chksum_sha2_256 as sha2_256;
+///
+/// let data = Bytes::from_static(b"example ").chain(Bytes::from_static(b"data"));
+/// let digest = sha2_256::hash_buf(data);
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// ```
+#[cfg(feature = "bytes")]
+#[must_use]
+pub fn hash_buf(data: impl bytes::Buf) -> Digest {
+    let mut hash = SHA2_256::new();
+    hash.update_buf(data);
+    hash.digest()
+}
+
 /// Computes the hash of the given input.
 ///
 /// # Example
@@ -396,10 +513,319 @@ pub async fn async_chksum(data: impl core::AsyncChksumable) -> Result<Digest> {
     core::async_chksum::<SHA2_256>(data).await
 }
 
+/// Computes the hash of the given input using the provided options.
+///
+/// The options can be reused between calls to avoid reallocating the read buffer.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256 as sha2_256;
+/// use chksum_sha2_256::ChksumOptions;
+///
+/// let mut options = ChksumOptions::with_buffer_size(64 * 1024);
+/// let data = b"example data";
+/// if let Ok(digest) = sha2_256::chksum_with(data, &mut options) {
+///     assert_eq!(
+///         digest.to_hex_lowercase(),
+///         "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+///     );
+/// }
+/// ```
+pub fn chksum_with(mut data: impl ChksumableWithOptions, options: &mut ChksumOptions) -> Result<Digest> {
+    let mut hash = SHA2_256::new();
+    data.chksum_with_options(&mut hash, options)?;
+    Ok(hash.digest())
+}
+
+/// Computes the hash of the given input using the provided options.
+///
+/// The options can be reused between calls to avoid reallocating the read buffer.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256 as sha2_256;
+/// use chksum_sha2_256::ChksumOptions;
+///
+/// # async fn wrapper() {
+/// let mut options = ChksumOptions::with_buffer_size(64 * 1024);
+/// let data = b"example data";
+/// if let Ok(digest) = sha2_256::async_chksum_with(data, &mut options).await {
+///     assert_eq!(
+///         digest.to_hex_lowercase(),
+///         "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+///     );
+/// }
+/// # }
+/// ```
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_chksum_with(
+    mut data: impl AsyncChksumableWithOptions,
+    options: &mut ChksumOptions,
+) -> Result<Digest> {
+    let mut hash = SHA2_256::new();
+    data.chksum_with_options(&mut hash, options).await?;
+    Ok(hash.digest())
+}
+
+/// Copies all data from the reader into the writer, computing the hash in the same pass.
+///
+/// Returns the number of bytes copied and the digest of the data. The writer is flushed once all data is copied.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256 as sha2_256;
+///
+/// # use chksum_sha2_256::Result;
+/// # fn wrapper() -> Result<()> {
+/// let mut destination = Vec::new();
+/// let (length, digest) = sha2_256::copy(&b"example data"[..], &mut destination)?;
+/// assert_eq!(length, 12);
+/// assert_eq!(destination, b"example data");
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when reading from the reader or writing to the writer fails.
+pub fn copy(reader: impl Read, mut writer: impl Write) -> Result<(u64, Digest)> {
+    let mut hash = SHA2_256::new();
+    let length = ChksumOptions::new().copy(reader, &mut writer, &mut hash)?;
+    writer.flush()?;
+    Ok((length, hash.digest()))
+}
+
+/// Copies all data from the reader into the writer, verifying it against the expected digest.
+///
+/// The data is passed to the writer as it is read, so the digest can only be checked once everything was written: on
+/// a mismatch the writer has already received the unverified data, and only the final flush is skipped. To keep
+/// unverified data away from its destination, copy into a writer whose content is committed separately, e.g. the
+/// `AtomicWriter` of the `writer` feature, and commit it only when this function succeeds.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256 as sha2_256;
+/// use chksum_sha2_256::Digest;
+///
+/// # use chksum_sha2_256::Result;
+/// # fn wrapper() -> Result<()> {
+/// let expected =
+///     Digest::try_from("44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061")
+///         .unwrap();
+/// let mut destination = Vec::new();
+/// let length = sha2_256::copy_verified(&b"example data"[..], &mut destination, expected)?;
+/// assert_eq!(length, 12);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error with [`ErrorKind::InvalidData`] when the digest of the copied data doesn't match the expected
+/// one, or any error from reading from the reader or writing to the writer.
+pub fn copy_verified(reader: impl Read, mut writer: impl Write, expected: Digest) -> Result<u64> {
+    let mut hash = SHA2_256::new();
+    let length = ChksumOptions::new().copy(reader, &mut writer, &mut hash)?;
+    verify_digest(expected, hash.digest())?;
+    writer.flush()?;
+    Ok(length)
+}
+
+/// Copies all data from the async reader into the async writer, computing the hash in the same pass.
+///
+/// Returns the number of bytes copied and the digest of the data. The writer is flushed once all data is copied.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256 as sha2_256;
+///
+/// # use chksum_sha2_256::Result;
+/// # async fn wrapper() -> Result<()> {
+/// let mut destination = Vec::new();
+/// let (length, digest) = sha2_256::async_copy(&b"example data"[..], &mut destination).await?;
+/// assert_eq!(length, 12);
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when reading from the reader or writing to the writer fails.
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_copy(
+    mut reader: impl tokio::io::AsyncRead + Unpin,
+    mut writer: impl tokio::io::AsyncWrite + Unpin,
+) -> Result<(u64, Digest)> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut hash = SHA2_256::new();
+    let length = ChksumOptions::new()
+        .async_copy(&mut reader, &mut writer, &mut hash)
+        .await?;
+    writer.flush().await?;
+    Ok((length, hash.digest()))
+}
+
+/// Copies all data from the async reader into the async writer, verifying it against the expected digest.
+///
+/// On a mismatch the writer has already received the unverified data, and only the final flush is skipped, see
+/// [`copy_verified`].
+///
+/// # Errors
+///
+/// Returns an error with [`ErrorKind::InvalidData`] when the digest of the copied data doesn't match the expected
+/// one, or any error from reading from the reader or writing to the writer.
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_copy_verified(
+    mut reader: impl tokio::io::AsyncRead + Unpin,
+    mut writer: impl tokio::io::AsyncWrite + Unpin,
+    expected: Digest,
+) -> Result<u64> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut hash = SHA2_256::new();
+    let length = ChksumOptions::new()
+        .async_copy(&mut reader, &mut writer, &mut hash)
+        .await?;
+    verify_digest(expected, hash.digest())?;
+    writer.flush().await?;
+    Ok(length)
+}
+
+/// Computes the hash of `length` bytes of the file starting at `offset`.
+///
+/// The file is repositioned to the offset, and only the requested range is read.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use chksum_sha2_256 as sha2_256;
+///
+/// # use chksum_sha2_256::Result;
+/// # fn wrapper() -> Result<()> {
+/// let file = Cursor::new(b"some example data");
+/// let digest = sha2_256::chksum_range(file, 5, 12)?;
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error with [`ErrorKind::UnexpectedEof`] when the file ends before the end of the range, or any error
+/// from seeking or reading the file.
+pub fn chksum_range(mut file: impl Read + Seek, offset: u64, length: u64) -> Result<Digest> {
+    let mut hash = SHA2_256::new();
+    file.seek(SeekFrom::Start(offset))?;
+    let read = ChksumOptions::new().update(file.take(length), &mut hash)?;
+    verify_range(offset, length, read)?;
+    Ok(hash.digest())
+}
+
+/// Computes the hash of `length` bytes of the async file starting at `offset`.
+///
+/// The file is repositioned to the offset, and only the requested range is read, see [`chksum_range`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use chksum_sha2_256 as sha2_256;
+///
+/// # use chksum_sha2_256::Result;
+/// # async fn wrapper() -> Result<()> {
+/// let file = Cursor::new(b"some example data");
+/// let digest = sha2_256::async_chksum_range(file, 5, 12).await?;
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error with [`ErrorKind::UnexpectedEof`] when the file ends before the end of the range, or any error
+/// from seeking or reading the file.
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_chksum_range(
+    mut file: impl tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+    offset: u64,
+    length: u64,
+) -> Result<Digest> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut hash = SHA2_256::new();
+    file.seek(SeekFrom::Start(offset)).await?;
+    let read = ChksumOptions::new()
+        .async_update(&mut file.take(length), &mut hash)
+        .await?;
+    verify_range(offset, length, read)?;
+    Ok(hash.digest())
+}
+
+/// Returns an error when fewer bytes than the length of the range were read.
+fn verify_range(offset: u64, length: u64, read: u64) -> io::Result<()> {
+    if read < length {
+        let end = offset.saturating_add(length);
+        let message = format!(
+            "file is shorter than the requested range `{offset}..{end}`, it ends at `{}`",
+            offset + read
+        );
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, message));
+    }
+    Ok(())
+}
+
+/// Returns an error when the digest of the data doesn't match the expected one.
+pub(crate) fn verify_digest(expected: Digest, actual: Digest) -> io::Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        let message = format!("digest mismatch, expected `{expected}`, got `{actual}`");
+        Err(io::Error::new(ErrorKind::InvalidData, message))
+    }
+}
+
+/// Returns an error when the size of the data doesn't match the expected one.
+pub(crate) fn verify_size(expected: u64, actual: u64) -> io::Result<()> {
+    if expected == actual {
+        Ok(())
+    } else {
+        let message = format!("size mismatch, expected {expected} bytes, got {actual} bytes");
+        Err(io::Error::new(ErrorKind::InvalidData, message))
+    }
+}
+
+#[cfg(not(feature = "hw-accel"))]
+type Update = hash::Update;
+#[cfg(feature = "hw-accel")]
+type Update = backend::Update;
+
 /// The SHA-2 256 hash instance.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct SHA2_256 {
-    inner: hash::Update,
+    inner: Update,
 }
 
 impl SHA2_256 {
@@ -427,6 +853,31 @@ impl SHA2_256 {
         hash.digest()
     }
 
+    /// Calculates the hash digests of many independent input data.
+    ///
+    /// Small inputs are processed in interleaved lanes, which is faster than calling [`SHA2_256::hash`] for each of
+    /// them. The digests are returned in the same order as the inputs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chksum_sha2_256::SHA2_256;
+    ///
+    /// let data = [&b"example data"[..], b"", b"example data"];
+    /// let digests = SHA2_256::hash_many(&data);
+    /// assert_eq!(
+    ///     digests[2].to_hex_lowercase(),
+    ///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn hash_many<T>(data: &[T]) -> Vec<Digest>
+    where
+        T: AsRef<[u8]>,
+    {
+        lanes::hash_many(data)
+    }
+
     /// Creates a new hash.
     ///
     /// # Example
@@ -444,10 +895,41 @@ impl SHA2_256 {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        let inner = hash::Update::new();
+        let inner = Update::new();
+        Self { inner }
+    }
+
+    /// Creates a new hash using the given backend instead of the [`active`](backend::active) one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chksum_sha2_256::backend::Backend;
+    /// use chksum_sha2_256::SHA2_256;
+    ///
+    /// let mut hash = SHA2_256::with_backend(Backend::Portable);
+    /// hash.update(b"example data");
+    /// assert_eq!(hash.backend(), Backend::Portable);
+    /// assert_eq!(hash.digest(), SHA2_256::hash(b"example data"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the backend isn't supported by the CPU, see [`Backend::is_supported`](backend::Backend::is_supported).
+    #[cfg(feature = "hw-accel")]
+    #[must_use]
+    pub fn with_backend(backend: backend::Backend) -> Self {
+        let inner = Update::with_backend(backend);
         Self { inner }
     }
 
+    /// Returns the backend used by this hash.
+    #[cfg(feature = "hw-accel")]
+    #[must_use]
+    pub fn backend(&self) -> backend::Backend {
+        self.inner.backend()
+    }
+
     /// Updates the hash state with an input data.
     ///
     /// # Example
@@ -472,6 +954,29 @@ impl SHA2_256 {
         self.inner.update(data);
     }
 
+    /// Updates the hash state with the remaining data of the buffer, chunk by chunk.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bytes::{Buf, BytesMut};
+    /// use chksum_sha2_256::SHA2_256;
+    ///
+    /// let mut hash = SHA2_256::new();
+    /// let mut data = BytesMut::from(&b"example data"[..]);
+    /// hash.update_buf(&mut data);
+    /// assert!(!data.has_remaining());
+    /// ```
+    #[cfg(feature = "bytes")]
+    pub fn update_buf(&mut self, mut data: impl bytes::Buf) {
+        while data.has_remaining() {
+            let chunk = data.chunk();
+            self.inner.update(chunk);
+            let length = chunk.len();
+            data.advance(length);
+        }
+    }
+
     /// Resets the hash state to its initial state.
     ///
     /// # Example
@@ -532,6 +1037,7 @@ impl core::Hash for SHA2_256 {
 }
 
 /// A hash digest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Digest(hash::Digest);
 
 impl Digest {
@@ -647,6 +1153,24 @@ impl UpperHex for Digest {
     }
 }
 
+impl StdHash for Digest {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl PartialOrd for Digest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Digest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
 impl From<[u8; hash::DIGEST_LENGTH_BYTES]> for Digest {
     fn from(digest: [u8; hash::DIGEST_LENGTH_BYTES]) -> Self {
         Self::new(digest)
@@ -658,3 +1182,11 @@ impl From<hash::Digest> for Digest {
         Self(digest)
     }
 }
+
+impl TryFrom<&str> for Digest {
+    type Error = hash::digest::FormatError;
+
+    fn try_from(digest: &str) -> std::result::Result<Self, Self::Error> {
+        hash::Digest::try_from(digest).map(Self)
+    }
+}
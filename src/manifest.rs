@@ -0,0 +1,532 @@
+//! Snapshots of directory trees and their differences.
+//!
+//! A [`Manifest`] records every entry below a directory with its type, permission bits, size and digest. It can be
+//! saved in a stable text format or as JSON, and later compared against another manifest or a live directory
+//! to list the entries which were added, removed, modified or changed their type, e.g. to check the integrity of an
+//! installation.
+//!
+//! # Entries
+//!
+//! Paths are relative to the snapshotted directory and use `/` as the separator. Symbolic links are not followed:
+//! their size is the length of the target path and their digest is the hash of the target path. Directories have
+//! neither size nor digest. Other file types, e.g. sockets or named pipes, are skipped.
+//!
+//! On Unix the mode holds the permission bits (`0o7777`). On other platforms it is `0o755` for directories, and
+//! `0o444` or `0o644` for other entries depending on whether they are read-only.
+//!
+//! # Text Format
+//!
+//! The first line is the `chksum-sha2-256 manifest v1` header, followed by one line per entry sorted by path:
+//!
+//! ```text
+//! <type> <mode> <size> <digest> <path>
+//! ```
+//!
+//! The type is `file`, `dir` or `symlink`, the mode is four octal digits, the digest is in lowercase hexadecimal and
+//! `-` stands for a missing size or digest. The path takes the rest of the line with backslashes and line feeds
+//! escaped as `\\` and `\n`.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::manifest::Manifest;
+//!
+//! # fn wrapper(root: &Path, saved: &str) -> Result<()> {
+//! let baseline: Manifest = saved.parse().unwrap();
+//! for change in baseline.diff_dir(root)? {
+//!     println!("{change}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::escape::{escape, unescape};
+use crate::json::{self, Value};
+use crate::options::permission_bits;
+use crate::{chksum_with, ChksumOptions, Digest, Result, SHA2_256};
+
+/// The first line of the text format.
+const HEADER: &str = "chksum-sha2-256 manifest v1";
+
+/// An error returned when parsing a manifest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The `chksum-sha2-256 manifest v1` header is missing.
+    MissingHeader,
+    /// The entry on the given line, counting from 1, is invalid.
+    InvalidEntry(usize),
+    /// The entry on the given line, counting from 1, repeats the path of a previous entry.
+    DuplicatePath(usize),
+    /// The JSON document is malformed or has no supported `version` on the given line, counting from 1.
+    InvalidJson(usize),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingHeader => f.write_str("missing manifest header"),
+            Self::InvalidEntry(line) => write!(f, "invalid manifest entry on line {line}"),
+            Self::DuplicatePath(line) => write!(f, "duplicate manifest path on line {line}"),
+            Self::InvalidJson(line) => write!(f, "invalid manifest JSON on line {line}"),
+        }
+    }
+}
+
+impl StdError for ParseError {}
+
+/// The type of a manifest entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// A regular file.
+    File,
+    /// A directory.
+    Directory,
+    /// A symbolic link.
+    Symlink,
+}
+
+impl EntryKind {
+    /// Returns the name used in the text and JSON formats.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Directory => "dir",
+            Self::Symlink => "symlink",
+        }
+    }
+}
+
+impl Display for EntryKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An entry of a [`Manifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Entry {
+    path: String,
+    kind: EntryKind,
+    mode: u32,
+    size: Option<u64>,
+    digest: Option<Digest>,
+}
+
+impl Entry {
+    /// Returns the path relative to the root of the manifest.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the type of the entry.
+    #[must_use]
+    pub const fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// Returns the permission bits.
+    #[must_use]
+    pub const fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    /// Returns the size in bytes, `None` for directories.
+    #[must_use]
+    pub const fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Returns the digest of the content, `None` for directories.
+    #[must_use]
+    pub const fn digest(&self) -> Option<Digest> {
+        self.digest
+    }
+}
+
+/// A difference between two manifests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The entry exists only in the newer manifest.
+    Added(Entry),
+    /// The entry exists only in the older manifest.
+    Removed(Entry),
+    /// The entry has the same type in both manifests but its mode, size or digest differ.
+    Modified {
+        /// The entry in the older manifest.
+        old: Entry,
+        /// The entry in the newer manifest.
+        new: Entry,
+    },
+    /// The entry has different types in the manifests.
+    TypeChanged {
+        /// The entry in the older manifest.
+        old: Entry,
+        /// The entry in the newer manifest.
+        new: Entry,
+    },
+}
+
+impl Change {
+    /// Returns the path of the changed entry.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added(entry) | Self::Removed(entry) => entry.path(),
+            Self::Modified { new, .. } | Self::TypeChanged { new, .. } => new.path(),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::Added(_) => "added",
+            Self::Removed(_) => "removed",
+            Self::Modified { .. } => "modified",
+            Self::TypeChanged { .. } => "type changed",
+        };
+        write!(f, "{status}: {}", self.path())
+    }
+}
+
+/// A snapshot of a directory tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    entries: Vec<Entry>,
+}
+
+impl Manifest {
+    /// Takes a snapshot of the given directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the directory cannot be read, or with [`ErrorKind::InvalidData`] when a path isn't valid
+    /// UTF-8.
+    pub fn snapshot(root: impl AsRef<Path>) -> Result<Self> {
+        Self::snapshot_with(root, &mut ChksumOptions::new())
+    }
+
+    /// Takes a snapshot of the given directory using the provided options to read files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the directory cannot be read, or with [`ErrorKind::InvalidData`] when a path isn't valid
+    /// UTF-8.
+    pub fn snapshot_with(root: impl AsRef<Path>, options: &mut ChksumOptions) -> Result<Self> {
+        let mut entries = Vec::new();
+        collect(root.as_ref(), "", options, &mut entries)?;
+        entries.sort_unstable_by(|left, right| left.path.cmp(&right.path));
+        Ok(Self { entries })
+    }
+
+    /// Returns the entries sorted by path.
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Returns the entry with the given path.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&Entry> {
+        self.entries
+            .binary_search_by(|entry| entry.path.as_str().cmp(path))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    /// Lists the changes from this manifest to the newer one, sorted by path.
+    #[must_use]
+    pub fn diff(&self, new: &Self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let mut old = self.entries.iter().peekable();
+        let mut new = new.entries.iter().peekable();
+        loop {
+            let change = match (old.peek(), new.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Change::Removed(old.next().unwrap().clone()),
+                (None, Some(_)) => Change::Added(new.next().unwrap().clone()),
+                (Some(left), Some(right)) if left.path < right.path => Change::Removed(old.next().unwrap().clone()),
+                (Some(left), Some(right)) if left.path > right.path => Change::Added(new.next().unwrap().clone()),
+                (Some(left), Some(right)) => {
+                    let (left, right) = (*left, *right);
+                    old.next();
+                    new.next();
+                    if left.kind != right.kind {
+                        Change::TypeChanged {
+                            old: left.clone(),
+                            new: right.clone(),
+                        }
+                    } else if left != right {
+                        Change::Modified {
+                            old: left.clone(),
+                            new: right.clone(),
+                        }
+                    } else {
+                        continue;
+                    }
+                },
+            };
+            changes.push(change);
+        }
+        changes
+    }
+
+    /// Takes a snapshot of the given directory and lists the changes from this manifest to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the directory cannot be read.
+    pub fn diff_dir(&self, root: impl AsRef<Path>) -> Result<Vec<Change>> {
+        Ok(self.diff(&Self::snapshot(root)?))
+    }
+
+    /// Returns the manifest in JSON format.
+    ///
+    /// The document is an object with the `version` set to `1` and the `entries` array. Each entry is an object with
+    /// the `path`, `type`, `mode` (four octal digits), `size` and `digest` (lowercase hexadecimal) members, the last
+    /// two being `null` for directories. Members are always written in this order, one entry per line.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\n  \"version\": 1,\n  \"entries\": [");
+        for (index, entry) in self.entries.iter().enumerate() {
+            let separator = if index == 0 { "\n" } else { ",\n" };
+            let size = entry.size.map_or_else(|| "null".to_owned(), |size| size.to_string());
+            let digest = entry
+                .digest
+                .map_or_else(|| "null".to_owned(), |digest| format!("\"{digest:x}\""));
+            let _ = write!(
+                json,
+                "{separator}    {{\"path\": {}, \"type\": \"{}\", \"mode\": \"{:04o}\", \"size\": {size}, \"digest\": \
+                 {digest}}}",
+                json::string(&entry.path),
+                entry.kind,
+                entry.mode,
+            );
+        }
+        if !self.entries.is_empty() {
+            json.push_str("\n  ");
+        }
+        json.push_str("]\n}\n");
+        json
+    }
+
+    /// Parses the manifest from the JSON format written by [`to_json`](Self::to_json).
+    ///
+    /// Any valid JSON document with the same structure is accepted, members the format doesn't define are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chksum_sha2_256::manifest::Manifest;
+    ///
+    /// let text = "chksum-sha2-256 manifest v1\ndir 0755 - - dir\n";
+    /// let manifest: Manifest = text.parse().unwrap();
+    /// assert_eq!(Manifest::from_json(&manifest.to_json()), Ok(manifest));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError::InvalidJson`] when the document is malformed or its version isn't `1`, and
+    /// [`ParseError::InvalidEntry`] or [`ParseError::DuplicatePath`] with the line of the offending entry.
+    pub fn from_json(document: &str) -> std::result::Result<Self, ParseError> {
+        let Value::Object(manifest) = json::parse(document).map_err(ParseError::InvalidJson)? else {
+            return Err(ParseError::InvalidJson(1));
+        };
+        if !matches!(manifest.get("version"), Some(Value::Number(version)) if version == "1") {
+            return Err(ParseError::InvalidJson(manifest.line));
+        }
+        let Some(Value::Array(entries)) = manifest.get("entries") else {
+            return Err(ParseError::InvalidJson(manifest.line));
+        };
+        let entries = entries.iter().map(|entry| {
+            match entry {
+                Value::Object(object) => {
+                    json_entry(object)
+                        .map(|entry| (object.line, entry))
+                        .ok_or(ParseError::InvalidEntry(object.line))
+                },
+                _ => Err(ParseError::InvalidEntry(manifest.line)),
+            }
+        });
+        Self::from_entries(entries)
+    }
+
+    /// Creates the manifest from the parsed entries and their line numbers, rejecting duplicate paths.
+    fn from_entries(
+        entries: impl Iterator<Item = std::result::Result<(usize, Entry), ParseError>>,
+    ) -> std::result::Result<Self, ParseError> {
+        let mut paths = HashSet::new();
+        let mut sorted = Vec::new();
+        for entry in entries {
+            let (number, entry) = entry?;
+            if !paths.insert(entry.path.clone()) {
+                return Err(ParseError::DuplicatePath(number));
+            }
+            sorted.push(entry);
+        }
+        sorted.sort_unstable_by(|left, right| left.path.cmp(&right.path));
+        Ok(Self { entries: sorted })
+    }
+}
+
+impl Display for Manifest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for entry in &self.entries {
+            let size = entry.size.map_or_else(|| "-".to_owned(), |size| size.to_string());
+            let digest = entry
+                .digest
+                .map_or_else(|| "-".to_owned(), |digest| format!("{digest:x}"));
+            let path = escape(&entry.path);
+            writeln!(f, "{} {:04o} {size} {digest} {path}", entry.kind, entry.mode)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = ParseError;
+
+    fn from_str(manifest: &str) -> std::result::Result<Self, Self::Err> {
+        let mut lines = manifest.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(ParseError::MissingHeader);
+        }
+        let entries = lines.enumerate().map(|(index, line)| {
+            // the header is the first line
+            let number = index + 2;
+            parse_entry(line)
+                .map(|entry| (number, entry))
+                .ok_or(ParseError::InvalidEntry(number))
+        });
+        Self::from_entries(entries)
+    }
+}
+
+/// Collects the entries of the given directory recursively.
+fn collect(dir: &Path, prefix: &str, options: &mut ChksumOptions, entries: &mut Vec<Entry>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_str().ok_or_else(|| {
+            let message = format!("path `{}` isn't valid UTF-8", entry.path().display());
+            io::Error::new(ErrorKind::InvalidData, message)
+        })?;
+        let path = format!("{prefix}{name}");
+        let metadata = fs::symlink_metadata(entry.path())?;
+        let mode = permission_bits(&metadata);
+        let file_type = metadata.file_type();
+        if file_type.is_symlink() {
+            let target = fs::read_link(entry.path())?;
+            let target = target.as_os_str().as_encoded_bytes();
+            let (size, digest) = (Some(target.len() as u64), Some(SHA2_256::hash(target)));
+            let kind = EntryKind::Symlink;
+            entries.push(Entry {
+                path,
+                kind,
+                mode,
+                size,
+                digest,
+            });
+        } else if file_type.is_dir() {
+            collect(&entry.path(), &format!("{path}/"), options, entries)?;
+            let (kind, size, digest) = (EntryKind::Directory, None, None);
+            entries.push(Entry {
+                path,
+                kind,
+                mode,
+                size,
+                digest,
+            });
+        } else if file_type.is_file() {
+            let digest = chksum_with(File::open(entry.path())?, options)?;
+            let (kind, size, digest) = (EntryKind::File, Some(metadata.len()), Some(digest));
+            entries.push(Entry {
+                path,
+                kind,
+                mode,
+                size,
+                digest,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Parses an entry line of the text format.
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut fields = line.splitn(5, ' ');
+    let kind = fields.next()?;
+    let mode = fields.next()?;
+    let size = Some(fields.next()?).filter(|&size| size != "-");
+    let digest = Some(fields.next()?).filter(|&digest| digest != "-");
+    let path = unescape(fields.next()?)?;
+    new_entry(path, kind, mode, size, digest)
+}
+
+/// Converts an entry object of the JSON format.
+fn json_entry(object: &json::Object) -> Option<Entry> {
+    let string = |name| {
+        match object.get(name)? {
+            Value::String(value) => Some(value.as_str()),
+            _ => None,
+        }
+    };
+    let size = match object.get("size")? {
+        Value::Null => None,
+        Value::Number(size) => Some(size.as_str()),
+        _ => return None,
+    };
+    let digest = match object.get("digest")? {
+        Value::Null => None,
+        Value::String(digest) => Some(digest.as_str()),
+        _ => return None,
+    };
+    let path = string("path").filter(|path| !path.is_empty())?;
+    new_entry(path.to_owned(), string("type")?, string("mode")?, size, digest)
+}
+
+/// Creates an entry from the fields shared by the text and JSON formats.
+fn new_entry(path: String, kind: &str, mode: &str, size: Option<&str>, digest: Option<&str>) -> Option<Entry> {
+    let kind = match kind {
+        "file" => EntryKind::File,
+        "dir" => EntryKind::Directory,
+        "symlink" => EntryKind::Symlink,
+        _ => return None,
+    };
+    if mode.len() != 4 {
+        return None;
+    }
+    let mode = u32::from_str_radix(mode, 8).ok()?;
+    let size = match size {
+        None => None,
+        Some(size) => Some(size.parse().ok()?),
+    };
+    let digest = match digest {
+        None => None,
+        Some(digest) if digest.bytes().any(|byte| byte.is_ascii_uppercase()) => return None,
+        Some(digest) => Some(Digest::try_from(digest).ok()?),
+    };
+    if (kind == EntryKind::Directory) != (size.is_none() && digest.is_none()) || size.is_some() != digest.is_some() {
+        return None;
+    }
+    Some(Entry {
+        path,
+        kind,
+        mode,
+        size,
+        digest,
+    })
+}
@@ -0,0 +1,458 @@
+//! The [`MerkleTree`] computes Merkle tree hashes as defined by [RFC 6962](https://www.rfc-editor.org/rfc/rfc6962)
+//! (Certificate Transparency).
+//!
+//! Leaves and interior nodes are domain-separated: a leaf hash is `SHA-256(0x00 || data)` and a node hash is
+//! `SHA-256(0x01 || left || right)`. The root of an empty tree is the hash of an empty string.
+//!
+//! Besides the root, the tree generates [`InclusionProof`]s, which prove that a leaf is a part of the tree, and
+//! [`ConsistencyProof`]s, which prove that a tree is an append-only extension of its older version.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::merkle::MerkleTree;
+//!
+//! let tree = MerkleTree::from_leaves(["first", "second", "third"]);
+//! let root = tree.root();
+//!
+//! let proof = tree.inclusion_proof(1).unwrap();
+//! assert!(proof.verify("second", &root));
+//! assert!(!proof.verify("fourth", &root));
+//! ```
+//!
+//! Trees can also be built from any [`Chksumable`] input, which is split into fixed-size chunks:
+//!
+//! ```rust
+//! # use std::path::Path;
+//! use std::fs::File;
+//!
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::merkle::MerkleTree;
+//!
+//! # fn wrapper(path: &Path) -> Result<()> {
+//! let file = File::open(path)?;
+//! let tree = MerkleTree::from_chunks(file, 4096)?;
+//! println!("{} chunks, root {}", tree.len(), tree.root());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::chunks::ChunkSplitter;
+use crate::{core, Chksumable, Digest, Result, SHA2_256};
+
+/// The prefix of the leaf hash input.
+const LEAF_PREFIX: u8 = 0x00;
+
+/// The prefix of the node hash input.
+const NODE_PREFIX: u8 = 0x01;
+
+/// Computes the hash of a leaf.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::merkle;
+///
+/// let digest = merkle::leaf_hash(b"");
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d"
+/// );
+/// ```
+#[must_use]
+pub fn leaf_hash(data: impl AsRef<[u8]>) -> Digest {
+    let mut hash = SHA2_256::new();
+    hash.update([LEAF_PREFIX]);
+    hash.update(data);
+    hash.digest()
+}
+
+/// Computes the hash of an interior node from the hashes of its children.
+#[must_use]
+pub fn node_hash(left: &Digest, right: &Digest) -> Digest {
+    let mut hash = SHA2_256::new();
+    hash.update([NODE_PREFIX]);
+    hash.update(left);
+    hash.update(right);
+    hash.digest()
+}
+
+/// Returns the largest power of two smaller than `size`.
+const fn split(size: usize) -> usize {
+    debug_assert!(size > 1, "size must be greater than one");
+    size.next_power_of_two() / 2
+}
+
+/// Computes the root of the subtree made of the given leaf hashes.
+fn subtree_root(leaves: &[Digest]) -> Digest {
+    match leaves {
+        [] => SHA2_256::new().digest(),
+        [leaf] => *leaf,
+        _ => {
+            let (left, right) = leaves.split_at(split(leaves.len()));
+            node_hash(&subtree_root(left), &subtree_root(right))
+        },
+    }
+}
+
+/// Collects the audit path of the leaf at the given index.
+fn inclusion_path(index: usize, leaves: &[Digest], path: &mut Vec<Digest>) {
+    if leaves.len() <= 1 {
+        return;
+    }
+    let (left, right) = leaves.split_at(split(leaves.len()));
+    if index < left.len() {
+        inclusion_path(index, left, path);
+        path.push(subtree_root(right));
+    } else {
+        inclusion_path(index - left.len(), right, path);
+        path.push(subtree_root(left));
+    }
+}
+
+/// Collects the consistency path between the first `size` leaves and all the leaves.
+fn consistency_path(size: usize, leaves: &[Digest], complete: bool, path: &mut Vec<Digest>) {
+    if size == leaves.len() {
+        if !complete {
+            path.push(subtree_root(leaves));
+        }
+        return;
+    }
+    let (left, right) = leaves.split_at(split(leaves.len()));
+    if size <= left.len() {
+        consistency_path(size, left, complete, path);
+        path.push(subtree_root(right));
+    } else {
+        consistency_path(size - left.len(), right, false, path);
+        path.push(subtree_root(left));
+    }
+}
+
+/// Shifts both values right until the lowest bit of `first` is set or `first` is zero.
+fn shift_while_even(first: &mut usize, second: &mut usize) {
+    while *first != 0 && *first & 1 == 0 {
+        *first >>= 1;
+        *second >>= 1;
+    }
+}
+
+/// A Merkle tree over SHA-2 256 leaf hashes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleTree {
+    leaves: Vec<Digest>,
+}
+
+impl MerkleTree {
+    /// Creates an empty tree.
+    #[must_use]
+    pub const fn new() -> Self {
+        let leaves = Vec::new();
+        Self { leaves }
+    }
+
+    /// Creates a tree from the given leaves.
+    #[must_use]
+    pub fn from_leaves<I>(leaves: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let leaves = leaves.into_iter().map(leaf_hash).collect();
+        Self { leaves }
+    }
+
+    /// Creates a tree from already computed leaf hashes.
+    #[must_use]
+    pub fn from_leaf_hashes(leaves: Vec<Digest>) -> Self {
+        Self { leaves }
+    }
+
+    /// Creates a tree from the given input split into chunks of `chunk_size` bytes.
+    ///
+    /// The last chunk may be shorter. An empty input produces an empty tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the input cannot be read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn from_chunks(mut data: impl Chksumable, chunk_size: usize) -> Result<Self> {
+        let mut chunks = Chunks::new(chunk_size);
+        data.chksum_with(&mut chunks)?;
+        Ok(chunks.finish())
+    }
+
+    /// Appends a leaf to the tree.
+    pub fn push(&mut self, leaf: impl AsRef<[u8]>) {
+        self.leaves.push(leaf_hash(leaf));
+    }
+
+    /// Appends an already computed leaf hash to the tree.
+    pub fn push_leaf_hash(&mut self, leaf_hash: Digest) {
+        self.leaves.push(leaf_hash);
+    }
+
+    /// Returns the number of leaves.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns `true` if the tree has no leaves.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Returns the leaf hashes.
+    #[must_use]
+    pub fn leaf_hashes(&self) -> &[Digest] {
+        &self.leaves
+    }
+
+    /// Computes the root hash of the tree.
+    #[must_use]
+    pub fn root(&self) -> Digest {
+        subtree_root(&self.leaves)
+    }
+
+    /// Computes the root hash of the tree made of the first `size` leaves.
+    ///
+    /// Returns `None` if `size` exceeds the number of leaves.
+    #[must_use]
+    pub fn root_at(&self, size: usize) -> Option<Digest> {
+        self.leaves.get(..size).map(subtree_root)
+    }
+
+    /// Generates the inclusion proof of the leaf at the given index.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    #[must_use]
+    pub fn inclusion_proof(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        inclusion_path(index, &self.leaves, &mut path);
+        Some(InclusionProof::new(index, self.leaves.len(), path))
+    }
+
+    /// Generates the consistency proof between the tree made of the first `old_size` leaves and the whole tree.
+    ///
+    /// Returns `None` if `old_size` is zero or exceeds the number of leaves.
+    #[must_use]
+    pub fn consistency_proof(&self, old_size: usize) -> Option<ConsistencyProof> {
+        if old_size == 0 || old_size > self.leaves.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        consistency_path(old_size, &self.leaves, true, &mut path);
+        Some(ConsistencyProof::new(old_size, self.leaves.len(), path))
+    }
+}
+
+impl<T> FromIterator<T> for MerkleTree
+where
+    T: AsRef<[u8]>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_leaves(iter)
+    }
+}
+
+/// A proof that a leaf is included in a tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    index: usize,
+    tree_size: usize,
+    path: Vec<Digest>,
+}
+
+impl InclusionProof {
+    /// Creates a new proof.
+    #[must_use]
+    pub fn new(index: usize, tree_size: usize, path: Vec<Digest>) -> Self {
+        Self { index, tree_size, path }
+    }
+
+    /// Returns the index of the leaf.
+    #[must_use]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the number of leaves in the tree.
+    #[must_use]
+    pub const fn tree_size(&self) -> usize {
+        self.tree_size
+    }
+
+    /// Returns the audit path, from the leaf up to the root.
+    #[must_use]
+    pub fn path(&self) -> &[Digest] {
+        &self.path
+    }
+
+    /// Verifies that the leaf is included in the tree with the given root.
+    #[must_use]
+    pub fn verify(&self, leaf: impl AsRef<[u8]>, root: &Digest) -> bool {
+        self.verify_leaf_hash(&leaf_hash(leaf), root)
+    }
+
+    /// Verifies that the leaf hash is included in the tree with the given root.
+    #[must_use]
+    pub fn verify_leaf_hash(&self, leaf_hash: &Digest, root: &Digest) -> bool {
+        if self.index >= self.tree_size {
+            return false;
+        }
+
+        let mut index = self.index;
+        let mut last = self.tree_size - 1;
+        let mut hash = *leaf_hash;
+        for sibling in &self.path {
+            if last == 0 {
+                return false;
+            }
+            if index & 1 == 1 || index == last {
+                hash = node_hash(sibling, &hash);
+                shift_while_even(&mut index, &mut last);
+            } else {
+                hash = node_hash(&hash, sibling);
+            }
+            index >>= 1;
+            last >>= 1;
+        }
+        last == 0 && hash == *root
+    }
+}
+
+/// A proof that a tree is an append-only extension of its older version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyProof {
+    old_size: usize,
+    new_size: usize,
+    path: Vec<Digest>,
+}
+
+impl ConsistencyProof {
+    /// Creates a new proof.
+    #[must_use]
+    pub fn new(old_size: usize, new_size: usize, path: Vec<Digest>) -> Self {
+        Self {
+            old_size,
+            new_size,
+            path,
+        }
+    }
+
+    /// Returns the number of leaves in the old tree.
+    #[must_use]
+    pub const fn old_size(&self) -> usize {
+        self.old_size
+    }
+
+    /// Returns the number of leaves in the new tree.
+    #[must_use]
+    pub const fn new_size(&self) -> usize {
+        self.new_size
+    }
+
+    /// Returns the proof nodes.
+    #[must_use]
+    pub fn path(&self) -> &[Digest] {
+        &self.path
+    }
+
+    /// Verifies that the tree with the new root extends the tree with the old root.
+    #[must_use]
+    pub fn verify(&self, old_root: &Digest, new_root: &Digest) -> bool {
+        if self.old_size == 0 || self.old_size > self.new_size {
+            return false;
+        }
+        if self.old_size == self.new_size {
+            return self.path.is_empty() && old_root == new_root;
+        }
+
+        let mut path = self.path.iter();
+        // the old root is omitted from the proof when the old tree is a complete subtree
+        let first = if self.old_size.is_power_of_two() {
+            old_root
+        } else if let Some(first) = path.next() {
+            first
+        } else {
+            return false;
+        };
+
+        let mut index = self.old_size - 1;
+        let mut last = self.new_size - 1;
+        while index & 1 == 1 {
+            index >>= 1;
+            last >>= 1;
+        }
+
+        let mut old_hash = *first;
+        let mut new_hash = *first;
+        for node in path {
+            if last == 0 {
+                return false;
+            }
+            if index & 1 == 1 || index == last {
+                old_hash = node_hash(node, &old_hash);
+                new_hash = node_hash(node, &new_hash);
+                shift_while_even(&mut index, &mut last);
+            } else {
+                new_hash = node_hash(&new_hash, node);
+            }
+            index >>= 1;
+            last >>= 1;
+        }
+        last == 0 && old_hash == *old_root && new_hash == *new_root
+    }
+}
+
+/// A hash which splits the incoming data into leaves of a fixed size.
+#[derive(Clone, Debug)]
+struct Chunks {
+    splitter: ChunkSplitter,
+}
+
+impl Chunks {
+    fn new(chunk_size: usize) -> Self {
+        let splitter = ChunkSplitter::new(chunk_size, &[LEAF_PREFIX]);
+        Self { splitter }
+    }
+
+    fn finish(self) -> MerkleTree {
+        MerkleTree::from_leaf_hashes(self.splitter.finish())
+    }
+}
+
+impl Default for Chunks {
+    fn default() -> Self {
+        Self::new(crate::options::DEFAULT_BUFFER_SIZE)
+    }
+}
+
+impl core::Hash for Chunks {
+    type Digest = Digest;
+
+    fn update<T>(&mut self, data: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.splitter.update(data.as_ref());
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.splitter.chunk_size());
+    }
+
+    fn digest(&self) -> Self::Digest {
+        self.clone().finish().root()
+    }
+}
@@ -0,0 +1,119 @@
+//! This module is optional and can be enabled using the `mmap` Cargo feature.
+//!
+//! The [`chksum_mmap`] function calculates the digest of a file by mapping it into memory instead of reading it in
+//! chunks, which is usually faster for large local files.
+//!
+//! Special files (pipes, character devices, procfs and sysfs entries), empty files and any other files which fail to
+//! be mapped are read through a buffer instead. When the file is truncated while it is being hashed, an error is
+//! returned.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `mmap` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["mmap"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features mmap
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256 as sha2_256;
+//!
+//! # fn wrapper(path: &Path) -> Result<()> {
+//! let digest = sha2_256::chksum_mmap(path)?;
+//! assert_eq!(
+//!     digest.to_hex_lowercase(),
+//!     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::{ChksumOptions, ChksumableWithOptions, Digest, Result, SHA2_256};
+
+/// The number of mapped bytes hashed between checks of the file length.
+const CHUNK_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Computes the hash of the file at the given path using a memory map.
+///
+/// Falls back to buffered reads when the file cannot be mapped.
+///
+/// # Errors
+///
+/// Returns an error when the file cannot be opened or read, or when it is truncated during hashing.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256 as sha2_256;
+///
+/// # fn wrapper(path: &Path) -> Result<()> {
+/// let digest = sha2_256::chksum_mmap(path)?;
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn chksum_mmap(path: impl AsRef<Path>) -> Result<Digest> {
+    let mut file = File::open(path)?;
+    let mut hash = SHA2_256::new();
+
+    let metadata = file.metadata()?;
+    let mappable = metadata.is_file() && metadata.len() > 0 && usize::try_from(metadata.len()).is_ok();
+    // pipes, devices and procfs entries report no meaningful length, and files larger than the address space
+    // cannot be mapped at all
+    let mmap = if mappable {
+        // SAFETY: The map is read-only and never outlives the file. Modifications of the underlying file by other
+        // processes are undefined behaviour in Rust terms; truncation is detected below before touching pages
+        // which are no longer backed by the file, which narrows (but cannot fully close) the window for `SIGBUS`.
+        #[allow(unsafe_code)]
+        unsafe { Mmap::map(&file) }.ok()
+    } else {
+        None
+    };
+    // sysfs attributes and some network filesystems report a length but refuse to be mapped
+    let Some(mmap) = mmap else {
+        let mut options = ChksumOptions::new();
+        file.chksum_with_options(&mut hash, &mut options)?;
+        return Ok(hash.digest());
+    };
+
+    for (index, chunk) in mmap.chunks(CHUNK_LENGTH).enumerate() {
+        let end = index * CHUNK_LENGTH + chunk.len();
+        ensure_length(&file, end)?;
+        hash.update(chunk);
+    }
+    ensure_length(&file, mmap.len())?;
+
+    Ok(hash.digest())
+}
+
+/// Returns an error when the file is shorter than the expected length.
+fn ensure_length(file: &File, length: usize) -> io::Result<()> {
+    let current = file.metadata()?.len();
+    if current < length as u64 {
+        let error = io::Error::new(ErrorKind::UnexpectedEof, "file was truncated during hashing");
+        return Err(error);
+    }
+    Ok(())
+}
@@ -0,0 +1,489 @@
+//! Content digests as defined by the [OCI image specification](https://github.com/opencontainers/image-spec).
+//!
+//! Container registries identify blobs and manifests by digests in the `algorithm:encoded` form, e.g.
+//! `sha256:3a6eb079...`. The [`OciDigest`] type parses and formats this grammar, [`chksum`] computes the digest of
+//! any input and [`ImageLayout`] writes and verifies blobs of an
+//! [OCI image layout](https://github.com/opencontainers/image-spec/blob/main/image-layout.md) directory.
+//!
+//! With the `reader` feature enabled, the [`BlobReader`] verifies a stream, e.g. a layer being downloaded, against
+//! its [`Descriptor`] while the data is read.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::oci::{self, OciDigest};
+//!
+//! # use chksum_sha2_256::Result;
+//! # fn wrapper() -> Result<()> {
+//! let digest = oci::chksum(b"data")?;
+//! assert_eq!(
+//!     digest.to_string(),
+//!     "sha256:3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7"
+//! );
+//!
+//! let parsed: OciDigest =
+//!     "sha256:3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7"
+//!         .parse()
+//!         .unwrap();
+//! assert_eq!(parsed, digest);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+#[cfg(feature = "reader")]
+use std::io::Read;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[cfg(feature = "reader")]
+use crate::verifying::VerifyingReader;
+use crate::{core, temporary, verify_size, Digest, Result, SHA2_256};
+
+/// The algorithm identifier of the SHA-2 256 hash.
+pub const ALGORITHM: &str = "sha256";
+
+/// The name of the file marking the root of an image layout.
+const LAYOUT_FILE: &str = "oci-layout";
+
+/// The content of the image layout marker file.
+const LAYOUT_CONTENT: &str = "{\"imageLayoutVersion\":\"1.0.0\"}";
+
+/// The name of the image index file.
+const INDEX_FILE: &str = "index.json";
+
+/// The content of an empty image index.
+const INDEX_CONTENT: &str = "{\"schemaVersion\":2,\"manifests\":[]}";
+
+/// The name of the directory with blobs.
+const BLOBS_DIR: &str = "blobs";
+
+/// An error returned when parsing of [`OciDigest`] fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The `:` separator between algorithm and encoded part is missing.
+    MissingSeparator,
+    /// The algorithm doesn't match the `[a-z0-9]+([+._-][a-z0-9]+)*` grammar.
+    InvalidAlgorithm,
+    /// The encoded part doesn't match the `[a-zA-Z0-9=_-]+` grammar or, for `sha256`, isn't 64 lowercase hex digits.
+    InvalidEncoded,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::MissingSeparator => "missing separator between algorithm and encoded part",
+            Self::InvalidAlgorithm => "invalid algorithm",
+            Self::InvalidEncoded => "invalid encoded part",
+        };
+        f.write_str(message)
+    }
+}
+
+impl StdError for ParseError {}
+
+/// A digest in the `algorithm:encoded` form.
+///
+/// Any registered or unregistered algorithm is accepted, but only `sha256` digests can be converted to [`Digest`]
+/// with [`OciDigest::digest`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OciDigest {
+    algorithm: String,
+    encoded: String,
+}
+
+impl OciDigest {
+    /// Returns the algorithm part, e.g. `sha256`.
+    #[must_use]
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    /// Returns the encoded part, e.g. hexadecimal representation of the hash.
+    #[must_use]
+    pub fn encoded(&self) -> &str {
+        &self.encoded
+    }
+
+    /// Returns the hash digest when the algorithm is `sha256`.
+    #[must_use]
+    pub fn digest(&self) -> Option<Digest> {
+        if self.algorithm == ALGORITHM {
+            Digest::try_from(self.encoded.as_str()).ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for OciDigest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.encoded)
+    }
+}
+
+impl FromStr for OciDigest {
+    type Err = ParseError;
+
+    fn from_str(digest: &str) -> std::result::Result<Self, Self::Err> {
+        let (algorithm, encoded) = digest.split_once(':').ok_or(ParseError::MissingSeparator)?;
+
+        let is_component = |component: &str| {
+            !component.is_empty()
+                && component
+                    .bytes()
+                    .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit())
+        };
+        if !algorithm.split(['+', '.', '_', '-']).all(is_component) {
+            return Err(ParseError::InvalidAlgorithm);
+        }
+
+        let is_valid = if algorithm == ALGORITHM {
+            encoded.len() == 64
+                && encoded
+                    .bytes()
+                    .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+        } else {
+            !encoded.is_empty()
+                && encoded
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'=' | b'_' | b'-'))
+        };
+        if !is_valid {
+            return Err(ParseError::InvalidEncoded);
+        }
+
+        let algorithm = algorithm.to_owned();
+        let encoded = encoded.to_owned();
+        Ok(Self { algorithm, encoded })
+    }
+}
+
+impl From<Digest> for OciDigest {
+    fn from(digest: Digest) -> Self {
+        let algorithm = ALGORITHM.to_owned();
+        let encoded = digest.to_hex_lowercase();
+        Self { algorithm, encoded }
+    }
+}
+
+impl PartialEq<Digest> for OciDigest {
+    fn eq(&self, other: &Digest) -> bool {
+        self.digest().as_ref() == Some(other)
+    }
+}
+
+/// Computes the digest of the given input, e.g. blob or manifest.
+///
+/// # Errors
+///
+/// Returns an error when the input cannot be read.
+pub fn chksum(data: impl core::Chksumable) -> Result<OciDigest> {
+    core::chksum::<SHA2_256>(data).map(OciDigest::from)
+}
+
+/// The description of targeted content, i.e. its media type, digest and size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Descriptor {
+    media_type: String,
+    digest: OciDigest,
+    size: u64,
+}
+
+impl Descriptor {
+    /// Creates a new descriptor.
+    #[must_use]
+    pub fn new(media_type: impl Into<String>, digest: OciDigest, size: u64) -> Self {
+        let media_type = media_type.into();
+        Self {
+            media_type,
+            digest,
+            size,
+        }
+    }
+
+    /// Creates a descriptor of the given blob.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chksum_sha2_256::oci::Descriptor;
+    ///
+    /// let descriptor = Descriptor::from_blob("application/vnd.oci.image.layer.v1.tar", b"data");
+    /// assert_eq!(descriptor.size(), 4);
+    /// ```
+    #[must_use]
+    pub fn from_blob(media_type: impl Into<String>, blob: impl AsRef<[u8]>) -> Self {
+        let blob = blob.as_ref();
+        let digest = SHA2_256::hash(blob).into();
+        Self::new(media_type, digest, blob.len() as u64)
+    }
+
+    /// Returns the media type of the content.
+    #[must_use]
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// Returns the digest of the content.
+    #[must_use]
+    pub const fn digest(&self) -> &OciDigest {
+        &self.digest
+    }
+
+    /// Returns the size of the content in bytes.
+    #[must_use]
+    pub const fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the expected hash digest or an error for algorithms other than `sha256`.
+    fn expected(&self) -> io::Result<Digest> {
+        self.digest.digest().ok_or_else(|| {
+            let message = format!("unsupported digest algorithm `{}`", self.digest.algorithm);
+            io::Error::new(ErrorKind::Unsupported, message)
+        })
+    }
+}
+
+/// Returns an error describing a mismatched digest.
+fn digest_mismatch(expected: &OciDigest, actual: Digest) -> io::Error {
+    let actual = OciDigest::from(actual);
+    let message = format!("digest mismatch, expected `{expected}`, got `{actual}`");
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// A reader verifying the data against a [`Descriptor`].
+///
+/// The data is hashed while it is read. An error with [`ErrorKind::InvalidData`] is returned as soon as more data
+/// than the descriptor size is read, or instead of the end of the stream when the size or the digest don't match.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Read;
+///
+/// use chksum_sha2_256::oci::{BlobReader, Descriptor};
+///
+/// # fn wrapper() -> std::io::Result<()> {
+/// let descriptor = Descriptor::from_blob("application/octet-stream", b"data");
+/// let mut reader = BlobReader::new(&b"data"[..], &descriptor)?;
+/// let mut buffer = Vec::new();
+/// reader.read_to_end(&mut buffer)?;
+///
+/// let mut reader = BlobReader::new(&b"tampered"[..], &descriptor)?;
+/// assert!(reader.read_to_end(&mut Vec::new()).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "reader")]
+#[derive(Debug)]
+pub struct BlobReader<R> {
+    inner: VerifyingReader<R>,
+}
+
+#[cfg(feature = "reader")]
+impl<R> BlobReader<R>
+where
+    R: Read,
+{
+    /// Creates a new reader verifying the data against the given descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::Unsupported`] when the descriptor digest doesn't use the `sha256`
+    /// algorithm.
+    pub fn new(inner: R, descriptor: &Descriptor) -> io::Result<Self> {
+        let expected = descriptor.expected()?;
+        let inner = VerifyingReader::new(inner, expected, Some(descriptor.size));
+        Ok(Self { inner })
+    }
+
+    /// Returns the number of bytes read so far.
+    #[must_use]
+    pub const fn bytes_read(&self) -> u64 {
+        self.inner.bytes_read()
+    }
+
+    /// Unwraps this `BlobReader`, returning the underlying reader.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+#[cfg(feature = "reader")]
+impl<R> Read for BlobReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// Reads the whole stream and verifies it against the given descriptor.
+///
+/// # Errors
+///
+/// Returns an error when the stream cannot be read, or its size or digest don't match the descriptor.
+#[cfg(feature = "reader")]
+pub fn verify(reader: impl Read, descriptor: &Descriptor) -> Result<()> {
+    let mut reader = BlobReader::new(reader, descriptor)?;
+    io::copy(&mut reader, &mut io::sink())?;
+    Ok(())
+}
+
+/// An [OCI image layout](https://github.com/opencontainers/image-spec/blob/main/image-layout.md) directory.
+///
+/// Blobs are stored in the `blobs/<algorithm>/<encoded>` files, so their paths are derived from their digests.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// use chksum_sha2_256::oci::ImageLayout;
+///
+/// # use chksum_sha2_256::Result;
+/// # fn wrapper(path: &Path) -> Result<()> {
+/// let layout = ImageLayout::init(path)?;
+/// let digest = layout.write_blob(b"data")?;
+/// assert!(layout.blob_path(&digest).is_file());
+/// assert!(layout.verify()?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageLayout {
+    root: PathBuf,
+}
+
+impl ImageLayout {
+    /// Creates the image layout in the given directory, or opens an existing one.
+    ///
+    /// The `oci-layout` file, the empty `index.json` file and the `blobs/sha256` directory are created unless they
+    /// already exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the directory cannot be created.
+    pub fn init(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join(BLOBS_DIR).join(ALGORITHM))?;
+        for (name, content) in [(LAYOUT_FILE, LAYOUT_CONTENT), (INDEX_FILE, INDEX_CONTENT)] {
+            let path = root.join(name);
+            if !path.exists() {
+                fs::write(path, content)?;
+            }
+        }
+        Ok(Self { root })
+    }
+
+    /// Opens an existing image layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::NotFound`] when the directory doesn't contain the `oci-layout` file.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        if !root.join(LAYOUT_FILE).is_file() {
+            let message = format!("`{LAYOUT_FILE}` file not found in `{}`", root.display());
+            return Err(io::Error::new(ErrorKind::NotFound, message).into());
+        }
+        Ok(Self { root })
+    }
+
+    /// Returns the root directory of the layout.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the path of the blob with the given digest.
+    #[must_use]
+    pub fn blob_path(&self, digest: &OciDigest) -> PathBuf {
+        self.root.join(BLOBS_DIR).join(&digest.algorithm).join(&digest.encoded)
+    }
+
+    /// Writes the blob and returns its digest.
+    ///
+    /// The blob is written to a uniquely named temporary file renamed afterwards, so a blob file is never partially
+    /// written, even by concurrent writers. Existing blobs are not rewritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the blob cannot be written.
+    pub fn write_blob(&self, blob: impl AsRef<[u8]>) -> Result<OciDigest> {
+        let blob = blob.as_ref();
+        let digest = OciDigest::from(SHA2_256::hash(blob));
+        let path = self.blob_path(&digest);
+        if !path.is_file() {
+            let (mut file, temporary) = temporary::create(&path)?;
+            let result = file.write_all(blob).and_then(|()| fs::rename(&temporary, &path));
+            if result.is_err() {
+                let _ = fs::remove_file(&temporary);
+            }
+            result?;
+        }
+        Ok(digest)
+    }
+
+    /// Verifies the blob against the given descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the blob cannot be read, or its size or digest don't match the descriptor.
+    pub fn verify_blob(&self, descriptor: &Descriptor) -> Result<()> {
+        let expected = descriptor.expected()?;
+        let file = File::open(self.blob_path(&descriptor.digest))?;
+        let size = file.metadata()?.len();
+        verify_size(descriptor.size, size)?;
+        let digest = core::chksum::<SHA2_256>(file)?;
+        if digest != expected {
+            return Err(digest_mismatch(&descriptor.digest, digest).into());
+        }
+        Ok(())
+    }
+
+    /// Verifies all `sha256` blobs against the digests their paths are derived from.
+    ///
+    /// Returns the digests of corrupted blobs. Files with names that aren't valid digests are reported too, while
+    /// blobs of other algorithms and temporary files of [`write_blob`](Self::write_blob) are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the layout or any of the blobs cannot be read.
+    pub fn verify(&self) -> Result<Vec<OciDigest>> {
+        if !self.root.join(LAYOUT_FILE).is_file() {
+            let message = format!("`{LAYOUT_FILE}` file not found in `{}`", self.root.display());
+            return Err(io::Error::new(ErrorKind::NotFound, message).into());
+        }
+
+        let mut paths = fs::read_dir(self.root.join(BLOBS_DIR).join(ALGORITHM))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .filter(|path| !matches!(path, Ok(path) if path.file_name().is_some_and(temporary::is_temporary)))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+
+        let mut corrupted = Vec::new();
+        for path in paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let digest = OciDigest {
+                algorithm: ALGORITHM.to_owned(),
+                encoded: name.into_owned(),
+            };
+            match digest.digest() {
+                Some(expected) if digest.encoded == expected.to_hex_lowercase() => {
+                    if core::chksum::<SHA2_256>(path)? != expected {
+                        corrupted.push(digest);
+                    }
+                },
+                _ => corrupted.push(digest),
+            }
+        }
+        Ok(corrupted)
+    }
+}
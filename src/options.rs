@@ -0,0 +1,661 @@
+//! The [`ChksumOptions`] allows tuning how files, directories and standard input are read while calculating the digest.
+//!
+//! By default, the data is read in chunks of [`DEFAULT_BUFFER_SIZE`] bytes. Use a larger buffer for fast storage
+//! (e.g. NVMe arrays) or network filesystems, where fewer and bigger reads perform better.
+//!
+//! The buffer is owned by the options and allocated lazily on first use, so the same [`ChksumOptions`] instance can
+//! be reused across many calls without reallocating.
+//!
+//! # Metadata
+//!
+//! By default, a directory digest covers only the content of the files below it. The options can include the
+//! permission bits, the executable flag and the targets of symbolic links as well, e.g. for reproducible-build checks
+//! where `chmod +x` on a script must change the fingerprint. Digests of single files never include metadata.
+//!
+//! When any metadata is included, every entry visited while hashing a directory, in the same order as without
+//! metadata (sorted by path, depth first), is preceded by a record with the following fields:
+//!
+//! * kind: one byte, `f` for a file, `d` for a directory or `l` for a symbolic link,
+//! * permission bits: two bytes, big-endian `mode & 0o7777` (only with [`include_permissions`]),
+//! * executable flag: one byte, `1` for a file with the owner execute bit set, `0` otherwise (only with
+//!   [`include_executable`]),
+//! * size: eight bytes, big-endian length of the file content (files only),
+//! * target: eight bytes, big-endian length of the target path, followed by the target path (symbolic links only).
+//!
+//! The file content follows the file record and the records of its entries follow a directory record. Symbolic links
+//! are followed unless [`include_symlink_targets`] is enabled; followed links are encoded as the file or directory
+//! they point to, otherwise the link record has no content. On platforms other than Unix the permission bits are
+//! `0o755` for directories and `0o444` or `0o644` for other entries depending on whether they are read-only, so the
+//! executable flag is always `0`.
+//!
+//! [`include_permissions`]: ChksumOptions::include_permissions
+//! [`include_executable`]: ChksumOptions::include_executable
+//! [`include_symlink_targets`]: ChksumOptions::include_symlink_targets
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! use std::fs::File;
+//!
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256 as sha2_256;
+//! use chksum_sha2_256::ChksumOptions;
+//!
+//! # fn wrapper(paths: &[&Path]) -> Result<()> {
+//! let mut options = ChksumOptions::with_buffer_size(1024 * 1024);
+//! for path in paths {
+//!     let file = File::open(path)?;
+//!     let digest = sha2_256::chksum_with(file, &mut options)?;
+//!     println!("{digest}  {}", path.display());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::{read_dir, read_link, symlink_metadata, DirEntry, File, Metadata, ReadDir};
+#[cfg(feature = "async-runtime-futures")]
+use std::future::poll_fn;
+use std::io::{self, ErrorKind, IsTerminal, Read, Stdin, StdinLock, Write};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "async-runtime-futures")]
+use std::pin::Pin;
+
+#[cfg(feature = "async-runtime-tokio")]
+use async_trait::async_trait;
+#[cfg(feature = "bytes")]
+use bytes::{Bytes, BytesMut};
+#[cfg(feature = "async-runtime-tokio")]
+use tokio::fs::{
+    metadata as tokio_metadata,
+    read_dir as tokio_read_dir,
+    read_link as tokio_read_link,
+    symlink_metadata as tokio_symlink_metadata,
+    DirEntry as TokioDirEntry,
+    File as TokioFile,
+    ReadDir as TokioReadDir,
+};
+#[cfg(feature = "async-runtime-tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _, Stdin as TokioStdin};
+
+use crate::{Error, Result, SHA2_256};
+
+/// The default size of the read buffer in bytes.
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Options used while reading the data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChksumOptions {
+    buffer_size: usize,
+    buffer: Vec<u8>,
+    permissions: bool,
+    executable: bool,
+    symlink_targets: bool,
+}
+
+impl ChksumOptions {
+    /// Creates new options with the [`DEFAULT_BUFFER_SIZE`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_buffer_size(DEFAULT_BUFFER_SIZE)
+    }
+
+    /// Creates new options with the given buffer size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer_size` is zero.
+    #[must_use]
+    pub fn with_buffer_size(buffer_size: usize) -> Self {
+        assert!(buffer_size > 0, "buffer size must be greater than zero");
+        let buffer = Vec::new();
+        Self {
+            buffer_size,
+            buffer,
+            permissions: false,
+            executable: false,
+            symlink_targets: false,
+        }
+    }
+
+    /// Creates new options reusing the given buffer.
+    ///
+    /// The buffer size is taken from the length of the buffer, or from its capacity when the buffer is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both length and capacity of the buffer are zero.
+    #[must_use]
+    pub fn with_buffer(buffer: Vec<u8>) -> Self {
+        let buffer_size = if buffer.is_empty() {
+            buffer.capacity()
+        } else {
+            buffer.len()
+        };
+        assert!(buffer_size > 0, "buffer size must be greater than zero");
+        Self {
+            buffer_size,
+            buffer,
+            permissions: false,
+            executable: false,
+            symlink_targets: false,
+        }
+    }
+
+    /// Returns the size of the read buffer in bytes.
+    #[must_use]
+    pub const fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Sets whether directory digests include the permission bits of their entries.
+    ///
+    /// See the [module documentation](self#metadata) for the encoding.
+    #[must_use]
+    pub const fn include_permissions(mut self, include: bool) -> Self {
+        self.permissions = include;
+        self
+    }
+
+    /// Sets whether directory digests include the executable flag of their entries.
+    ///
+    /// See the [module documentation](self#metadata) for the encoding.
+    #[must_use]
+    pub const fn include_executable(mut self, include: bool) -> Self {
+        self.executable = include;
+        self
+    }
+
+    /// Sets whether directory digests include the targets of symbolic links instead of following them.
+    ///
+    /// See the [module documentation](self#metadata) for the encoding.
+    #[must_use]
+    pub const fn include_symlink_targets(mut self, include: bool) -> Self {
+        self.symlink_targets = include;
+        self
+    }
+
+    /// Returns `true` if directory digests include the permission bits.
+    #[must_use]
+    pub const fn includes_permissions(&self) -> bool {
+        self.permissions
+    }
+
+    /// Returns `true` if directory digests include the executable flag.
+    #[must_use]
+    pub const fn includes_executable(&self) -> bool {
+        self.executable
+    }
+
+    /// Returns `true` if directory digests include the targets of symbolic links.
+    #[must_use]
+    pub const fn includes_symlink_targets(&self) -> bool {
+        self.symlink_targets
+    }
+
+    /// Returns `true` if directory digests include any metadata.
+    pub(crate) const fn includes_metadata(&self) -> bool {
+        self.permissions || self.executable || self.symlink_targets
+    }
+
+    /// Returns the record of a file, see the [module documentation](self#metadata).
+    pub(crate) fn file_record(&self, mode: u32, size: u64) -> Vec<u8> {
+        let mut record = self.record(b'f', mode, mode & 0o100 != 0);
+        record.extend_from_slice(&size.to_be_bytes());
+        record
+    }
+
+    /// Returns the record of a directory, see the [module documentation](self#metadata).
+    pub(crate) fn directory_record(&self, mode: u32) -> Vec<u8> {
+        self.record(b'd', mode, false)
+    }
+
+    /// Returns the record of a symbolic link, see the [module documentation](self#metadata).
+    pub(crate) fn symlink_record(&self, mode: u32, target: &[u8]) -> Vec<u8> {
+        let mut record = self.record(b'l', mode, false);
+        record.extend_from_slice(&(target.len() as u64).to_be_bytes());
+        record.extend_from_slice(target);
+        record
+    }
+
+    /// Returns the fields of a record common to all kinds of entries.
+    fn record(&self, kind: u8, mode: u32, executable: bool) -> Vec<u8> {
+        let mut record = vec![kind];
+        if self.permissions {
+            #[allow(clippy::cast_possible_truncation)]
+            record.extend_from_slice(&((mode & 0o7777) as u16).to_be_bytes());
+        }
+        if self.executable {
+            record.push(u8::from(executable));
+        }
+        record
+    }
+
+    /// Consumes the options, returning the buffer so it can be reused elsewhere.
+    #[must_use]
+    pub fn into_buffer(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    /// Returns the read buffer, allocating it on first use.
+    pub(crate) fn buffer(&mut self) -> &mut [u8] {
+        if self.buffer.len() < self.buffer_size {
+            self.buffer.resize(self.buffer_size, 0);
+        }
+        &mut self.buffer[..self.buffer_size]
+    }
+
+    /// Reads the whole reader and updates the hash with its data, returning the number of bytes read.
+    pub(crate) fn update(&mut self, mut reader: impl Read, hash: &mut SHA2_256) -> io::Result<u64> {
+        let buffer = self.buffer();
+        let mut total = 0;
+        loop {
+            let length = match reader.read(buffer) {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            hash.update(&buffer[..length]);
+            total += length as u64;
+        }
+        Ok(total)
+    }
+
+    /// Reads the whole async reader and updates the hash with its data, returning the number of bytes read.
+    #[cfg(feature = "async-runtime-tokio")]
+    pub(crate) async fn async_update<R>(&mut self, reader: &mut R, hash: &mut SHA2_256) -> io::Result<u64>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+    {
+        let buffer = self.buffer();
+        let mut total = 0;
+        loop {
+            let length = match reader.read(buffer).await {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            hash.update(&buffer[..length]);
+            total += length as u64;
+        }
+        Ok(total)
+    }
+
+    /// Copies the whole reader into the writer and updates the hash with its data, returning the number of bytes
+    /// copied.
+    ///
+    /// The writer isn't flushed.
+    pub(crate) fn copy(
+        &mut self,
+        mut reader: impl Read,
+        mut writer: impl Write,
+        hash: &mut SHA2_256,
+    ) -> io::Result<u64> {
+        let buffer = self.buffer();
+        let mut total = 0;
+        loop {
+            let length = match reader.read(buffer) {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            hash.update(&buffer[..length]);
+            writer.write_all(&buffer[..length])?;
+            total += length as u64;
+        }
+        Ok(total)
+    }
+
+    /// Copies the whole async reader into the async writer and updates the hash with its data, returning the number
+    /// of bytes copied.
+    ///
+    /// The writer isn't flushed.
+    #[cfg(feature = "async-runtime-tokio")]
+    pub(crate) async fn async_copy<R, W>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+        hash: &mut SHA2_256,
+    ) -> io::Result<u64>
+    where
+        R: AsyncRead + Unpin + ?Sized,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let buffer = self.buffer();
+        let mut total = 0;
+        loop {
+            let length = match reader.read(buffer).await {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            hash.update(&buffer[..length]);
+            writer.write_all(&buffer[..length]).await?;
+            total += length as u64;
+        }
+        Ok(total)
+    }
+
+    /// Reads the whole futures-io reader and updates the hash with its data, returning the number of bytes read.
+    #[cfg(feature = "async-runtime-futures")]
+    pub(crate) async fn futures_update<R>(&mut self, reader: &mut R, hash: &mut SHA2_256) -> io::Result<u64>
+    where
+        R: futures_io::AsyncRead + Unpin + ?Sized,
+    {
+        let buffer = self.buffer();
+        let mut total = 0;
+        loop {
+            let length = match poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, buffer)).await {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            hash.update(&buffer[..length]);
+            total += length as u64;
+        }
+        Ok(total)
+    }
+}
+
+/// Returns the permission bits of the entry.
+#[cfg(unix)]
+pub(crate) fn permission_bits(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+/// Returns the permission bits of the entry.
+#[cfg(not(unix))]
+pub(crate) fn permission_bits(metadata: &Metadata) -> u32 {
+    if metadata.is_dir() {
+        0o755
+    } else if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+/// Hashes a directory entry preceded by its metadata record.
+fn chksum_entry_with_metadata(path: &Path, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+    if options.symlink_targets {
+        let metadata = symlink_metadata(path)?;
+        if metadata.is_symlink() {
+            let target = read_link(path)?;
+            let target = target.as_os_str().as_encoded_bytes();
+            hash.update(options.symlink_record(permission_bits(&metadata), target));
+            return Ok(());
+        }
+    }
+    let metadata = path.metadata()?;
+    if metadata.is_dir() {
+        hash.update(options.directory_record(permission_bits(&metadata)));
+        read_dir(path)?.chksum_with_options(hash, options)
+    } else {
+        hash.update(options.file_record(permission_bits(&metadata), metadata.len()));
+        File::open(path)?.chksum_with_options(hash, options)
+    }
+}
+
+/// Hashes a directory entry preceded by its metadata record.
+#[cfg(feature = "async-runtime-tokio")]
+async fn async_chksum_entry_with_metadata(path: &Path, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+    if options.symlink_targets {
+        let metadata = tokio_symlink_metadata(path).await?;
+        if metadata.is_symlink() {
+            let target = tokio_read_link(path).await?;
+            let target = target.as_os_str().as_encoded_bytes();
+            hash.update(options.symlink_record(permission_bits(&metadata), target));
+            return Ok(());
+        }
+    }
+    let metadata = tokio_metadata(path).await?;
+    if metadata.is_dir() {
+        hash.update(options.directory_record(permission_bits(&metadata)));
+        AsyncChksumableWithOptions::chksum_with_options(&mut tokio_read_dir(path).await?, hash, options).await
+    } else {
+        hash.update(options.file_record(permission_bits(&metadata), metadata.len()));
+        AsyncChksumableWithOptions::chksum_with_options(&mut TokioFile::open(path).await?, hash, options).await
+    }
+}
+
+impl Default for ChksumOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A trait for objects which can be hashed with the given [`ChksumOptions`].
+///
+/// The digests are the same as the ones calculated by [`Chksumable`](crate::Chksumable).
+pub trait ChksumableWithOptions {
+    /// Updates the given hash instance with the data from the object.
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()>;
+}
+
+macro_rules! impl_chksumable_with_options {
+    ($($t:ty),+ => $i:tt) => {
+        $(
+            impl ChksumableWithOptions for $t $i
+        )*
+    };
+}
+
+impl_chksumable_with_options!(&[u8], Vec<u8>, &Vec<u8>, &str, String, &String => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+});
+
+#[cfg(feature = "bytes")]
+impl_chksumable_with_options!(Bytes, &Bytes, BytesMut, &BytesMut => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+});
+
+impl<const LENGTH: usize> ChksumableWithOptions for [u8; LENGTH] {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+}
+
+impl<const LENGTH: usize> ChksumableWithOptions for &[u8; LENGTH] {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+}
+
+impl_chksumable_with_options!(Path, &Path, &mut Path => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        let metadata = self.metadata()?;
+        if metadata.is_dir() {
+            read_dir(self)?.chksum_with_options(hash, options)
+        } else {
+            // everything treat as a file when it is not a directory
+            File::open(self)?.chksum_with_options(hash, options)
+        }
+    }
+});
+
+impl_chksumable_with_options!(PathBuf, &PathBuf, &mut PathBuf => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        ChksumableWithOptions::chksum_with_options(&mut self.as_path(), hash, options)
+    }
+});
+
+impl_chksumable_with_options!(File, &File, &mut File => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        if self.is_terminal() {
+            return Err(Error::IsTerminal);
+        }
+
+        options.update(self, hash)?;
+        Ok(())
+    }
+});
+
+impl_chksumable_with_options!(DirEntry, &DirEntry, &mut DirEntry => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        ChksumableWithOptions::chksum_with_options(&mut self.path(), hash, options)
+    }
+});
+
+impl_chksumable_with_options!(ReadDir, &mut ReadDir => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        let dir_entries: io::Result<Vec<DirEntry>> = self.collect();
+        let mut dir_entries = dir_entries?;
+        dir_entries.sort_by_key(DirEntry::path);
+        dir_entries.into_iter().try_for_each(|mut dir_entry| {
+            if options.includes_metadata() {
+                chksum_entry_with_metadata(&dir_entry.path(), hash, options)
+            } else {
+                dir_entry.chksum_with_options(hash, options)
+            }
+        })?;
+        Ok(())
+    }
+});
+
+impl_chksumable_with_options!(Stdin, &Stdin, &mut Stdin => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        self.lock().chksum_with_options(hash, options)
+    }
+});
+
+impl_chksumable_with_options!(StdinLock<'_>, &mut StdinLock<'_> => {
+    fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        if self.is_terminal() {
+            return Err(Error::IsTerminal);
+        }
+
+        options.update(self, hash)?;
+        Ok(())
+    }
+});
+
+/// A trait for objects which can be hashed asynchronously with the given [`ChksumOptions`].
+///
+/// The digests are the same as the ones calculated by [`AsyncChksumable`](crate::AsyncChksumable).
+#[cfg(feature = "async-runtime-tokio")]
+#[async_trait]
+pub trait AsyncChksumableWithOptions: Send {
+    /// Updates the given hash instance with the data from the object.
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()>;
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+macro_rules! impl_async_chksumable_with_options {
+    ($($t:ty),+ => $i:tt) => {
+        $(
+            #[async_trait]
+            impl AsyncChksumableWithOptions for $t $i
+        )*
+    };
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(&[u8], Vec<u8>, &Vec<u8>, &str, String, &String => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+});
+
+#[cfg(all(feature = "bytes", feature = "async-runtime-tokio"))]
+impl_async_chksumable_with_options!(Bytes, &Bytes, BytesMut, &BytesMut => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+});
+
+#[cfg(feature = "async-runtime-tokio")]
+#[async_trait]
+impl<const LENGTH: usize> AsyncChksumableWithOptions for [u8; LENGTH] {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+#[async_trait]
+impl<const LENGTH: usize> AsyncChksumableWithOptions for &[u8; LENGTH] {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, _: &mut ChksumOptions) -> Result<()> {
+        hash.update(self);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(Path, &Path, &mut Path => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        let metadata = tokio_metadata(&self).await?;
+        if metadata.is_dir() {
+            AsyncChksumableWithOptions::chksum_with_options(&mut tokio_read_dir(self).await?, hash, options).await
+        } else {
+            // everything treat as a file when it is not a directory
+            AsyncChksumableWithOptions::chksum_with_options(&mut TokioFile::open(self).await?, hash, options).await
+        }
+    }
+});
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(PathBuf, &PathBuf, &mut PathBuf => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        AsyncChksumableWithOptions::chksum_with_options(&mut self.as_path(), hash, options).await
+    }
+});
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(TokioFile, &mut TokioFile => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        options.async_update(self, hash).await?;
+        Ok(())
+    }
+});
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(TokioDirEntry, &TokioDirEntry, &mut TokioDirEntry => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        AsyncChksumableWithOptions::chksum_with_options(&mut self.path(), hash, options).await
+    }
+});
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(TokioReadDir, &mut TokioReadDir => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        let mut dir_entries = Vec::new();
+        while let Some(dir_entry) = self.next_entry().await? {
+            dir_entries.push(dir_entry);
+        }
+        dir_entries.sort_by_key(TokioDirEntry::path);
+        for mut dir_entry in dir_entries {
+            if options.includes_metadata() {
+                async_chksum_entry_with_metadata(&dir_entry.path(), hash, options).await?;
+            } else {
+                AsyncChksumableWithOptions::chksum_with_options(&mut dir_entry, hash, options).await?;
+            }
+        }
+        Ok(())
+    }
+});
+
+#[cfg(feature = "async-runtime-tokio")]
+impl_async_chksumable_with_options!(TokioStdin, &mut TokioStdin => {
+    async fn chksum_with_options(&mut self, hash: &mut SHA2_256, options: &mut ChksumOptions) -> Result<()> {
+        options.async_update(self, hash).await?;
+        Ok(())
+    }
+});
@@ -0,0 +1,237 @@
+//! Composite checksums of multipart uploads as reported by
+//! [Amazon S3](https://docs.aws.amazon.com/AmazonS3/latest/userguide/checking-object-integrity.html).
+//!
+//! For an object uploaded in parts, S3 reports a checksum of checksums: the SHA-2 256 digest of the concatenated
+//! binary digests of the parts, encoded with Base64 and followed by the number of parts, e.g.
+//! `8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-2`. Use [`chksum`] with the part size of the upload to compute the
+//! composite checksum of a local file, and [`verify`] to check it against a reported value.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! use std::fs::File;
+//!
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256::s3;
+//!
+//! # fn wrapper(path: &Path) -> Result<()> {
+//! let checksum = s3::chksum(File::open(path)?, 8 * 1024 * 1024)?;
+//! println!("{} parts, checksum {checksum}", checksum.parts().len());
+//!
+//! s3::verify(
+//!     "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-2",
+//!     File::open(path)?,
+//!     8 * 1024 * 1024,
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, ErrorKind, Read};
+use std::str::FromStr;
+
+use crate::{base64, hash, ChksumOptions, Digest, Result, SHA2_256};
+
+/// An error returned when parsing of a composite checksum fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The value has no `-N` suffix with the number of parts.
+    MissingPartCount,
+    /// The number of parts isn't a positive integer.
+    InvalidPartCount,
+    /// The checksum isn't a Base64 encoded 32 bytes long digest.
+    InvalidDigest,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::MissingPartCount => "missing part count",
+            Self::InvalidPartCount => "invalid part count",
+            Self::InvalidDigest => "invalid digest",
+        };
+        f.write_str(message)
+    }
+}
+
+impl StdError for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(error: ParseError) -> Self {
+        Self::new(ErrorKind::InvalidData, error)
+    }
+}
+
+/// The composite checksum of a multipart upload, i.e. the checksum of checksums and the number of parts.
+///
+/// Formats and parses as `base64-N`, surrounding double quotes of ETag-like values are accepted when parsing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompositeChecksum {
+    digest: Digest,
+    part_count: usize,
+}
+
+impl CompositeChecksum {
+    /// Creates a composite checksum from the checksum of checksums and the number of parts.
+    #[must_use]
+    pub const fn new(digest: Digest, part_count: usize) -> Self {
+        Self { digest, part_count }
+    }
+
+    /// Computes the composite checksum of the given part digests.
+    #[must_use]
+    pub fn from_parts(parts: &[Digest]) -> Self {
+        let mut hash = SHA2_256::new();
+        for part in parts {
+            hash.update(part);
+        }
+        Self::new(hash.digest(), parts.len())
+    }
+
+    /// Returns the checksum of checksums.
+    #[must_use]
+    pub const fn digest(&self) -> Digest {
+        self.digest
+    }
+
+    /// Returns the number of parts.
+    #[must_use]
+    pub const fn part_count(&self) -> usize {
+        self.part_count
+    }
+}
+
+impl Display for CompositeChecksum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", base64::encode(self.digest), self.part_count)
+    }
+}
+
+impl FromStr for CompositeChecksum {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or(value);
+        let (encoded, part_count) = value.rsplit_once('-').ok_or(ParseError::MissingPartCount)?;
+        if !part_count.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(ParseError::InvalidPartCount);
+        }
+        let part_count = part_count
+            .parse()
+            .ok()
+            .filter(|&count| count > 0)
+            .ok_or(ParseError::InvalidPartCount)?;
+        let bytes = base64::decode(encoded).ok_or(ParseError::InvalidDigest)?;
+        let bytes: [u8; hash::DIGEST_LENGTH_BYTES] = bytes.try_into().map_err(|_| ParseError::InvalidDigest)?;
+        Ok(Self::new(Digest::new(bytes), part_count))
+    }
+}
+
+/// The digests of the parts of a multipart upload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultipartChecksum {
+    part_size: u64,
+    parts: Vec<Digest>,
+}
+
+impl MultipartChecksum {
+    /// Returns the size of the parts, the last part may be shorter.
+    #[must_use]
+    pub const fn part_size(&self) -> u64 {
+        self.part_size
+    }
+
+    /// Returns the digests of the parts in the order of upload.
+    #[must_use]
+    pub fn parts(&self) -> &[Digest] {
+        &self.parts
+    }
+
+    /// Returns the composite checksum of the parts.
+    #[must_use]
+    pub fn composite(&self) -> CompositeChecksum {
+        CompositeChecksum::from_parts(&self.parts)
+    }
+}
+
+impl Display for MultipartChecksum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.composite().fmt(f)
+    }
+}
+
+/// Computes the digests of the parts of `part_size` bytes and the composite checksum of the data, e.g. of a file.
+///
+/// The last part may be shorter. Empty data is uploaded as a single empty part.
+///
+/// # Example
+///
+/// ```rust
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256::s3;
+///
+/// # fn wrapper() -> Result<()> {
+/// let checksum = s3::chksum(&b"example data"[..], 8)?;
+/// assert_eq!(checksum.parts().len(), 2);
+/// assert_eq!(
+///     checksum.to_string(),
+///     "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-2"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the data cannot be read.
+///
+/// # Panics
+///
+/// Panics if `part_size` is zero.
+pub fn chksum(mut reader: impl Read, part_size: u64) -> Result<MultipartChecksum> {
+    assert!(part_size > 0, "part size must be greater than zero");
+    let mut options = ChksumOptions::new();
+    let mut parts = Vec::new();
+    loop {
+        let mut hash = SHA2_256::new();
+        let length = options.update((&mut reader).take(part_size), &mut hash)?;
+        if length == 0 && !parts.is_empty() {
+            break;
+        }
+        parts.push(hash.digest());
+        if length < part_size {
+            break;
+        }
+    }
+    Ok(MultipartChecksum { part_size, parts })
+}
+
+/// Verifies the data against a composite checksum reported by S3, e.g.
+/// `8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-2`.
+///
+/// The part size must be the one used for the upload.
+///
+/// # Errors
+///
+/// Returns an error with [`ErrorKind::InvalidData`] when the value is malformed or the checksum of the data doesn't
+/// match, or any error from reading the data.
+///
+/// # Panics
+///
+/// Panics if `part_size` is zero.
+pub fn verify(value: &str, reader: impl Read, part_size: u64) -> Result<()> {
+    let expected: CompositeChecksum = value.parse().map_err(io::Error::from)?;
+    let actual = chksum(reader, part_size)?.composite();
+    if actual != expected {
+        let message = format!("checksum mismatch, expected `{expected}`, got `{actual}`");
+        return Err(io::Error::new(ErrorKind::InvalidData, message).into());
+    }
+    Ok(())
+}
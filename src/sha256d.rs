@@ -0,0 +1,144 @@
+//! The SHA-256d hash function, i.e. SHA-2 256 applied twice, as used by Bitcoin for block and transaction
+//! identifiers.
+//!
+//! The interface mirrors the top-level one of the crate: use [`chksum`] to calculate the digest of file, directory
+//! and so on, or [`SHA256D`] for incremental hashing. With the `reader` and `writer` features enabled, the
+//! [`reader`] and [`writer`] modules provide on-the-fly hashing of streams.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::sha256d;
+//!
+//! let digest = sha256d::hash(b"hello");
+//! assert_eq!(
+//!     digest.to_hex_lowercase(),
+//!     "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+//! );
+//! ```
+
+#[cfg(feature = "reader")]
+pub mod reader;
+#[cfg(feature = "writer")]
+pub mod writer;
+
+#[cfg(feature = "reader")]
+#[doc(inline)]
+pub use self::reader::Reader;
+#[cfg(feature = "writer")]
+#[doc(inline)]
+pub use self::writer::Writer;
+use crate::{core, Digest, Result, SHA2_256};
+
+/// Creates a new hash.
+#[must_use]
+pub fn new() -> SHA256D {
+    SHA256D::new()
+}
+
+/// Computes the hash of the given input.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::sha256d;
+///
+/// let digest = sha256d::hash(b"hello");
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+/// );
+/// ```
+#[must_use]
+pub fn hash(data: impl core::Hashable) -> Digest {
+    core::hash::<SHA256D>(data)
+}
+
+/// Computes the hash of the given input.
+///
+/// # Example
+///
+/// ```rust
+/// use chksum_sha2_256::sha256d;
+///
+/// let data = b"hello";
+/// if let Ok(digest) = sha256d::chksum(data) {
+///     assert_eq!(
+///         digest.to_hex_lowercase(),
+///         "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50"
+///     );
+/// }
+/// ```
+pub fn chksum(data: impl core::Chksumable) -> Result<Digest> {
+    core::chksum::<SHA256D>(data)
+}
+
+/// Computes the hash of the given input.
+#[cfg(feature = "async-runtime-tokio")]
+pub async fn async_chksum(data: impl core::AsyncChksumable) -> Result<Digest> {
+    core::async_chksum::<SHA256D>(data).await
+}
+
+/// The SHA-256d hash instance.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SHA256D {
+    inner: SHA2_256,
+}
+
+impl SHA256D {
+    /// Calculates the hash digest of an input data.
+    #[must_use]
+    pub fn hash<T>(data: T) -> Digest
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut hash = Self::new();
+        hash.update(data);
+        hash.digest()
+    }
+
+    /// Creates a new hash.
+    #[must_use]
+    pub fn new() -> Self {
+        let inner = SHA2_256::new();
+        Self { inner }
+    }
+
+    /// Updates the hash state with an input data.
+    pub fn update<T>(&mut self, data: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.inner.update(data);
+    }
+
+    /// Resets the hash state to its initial state.
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    /// Produces the hash digest.
+    #[must_use]
+    pub fn digest(&self) -> Digest {
+        SHA2_256::hash(self.inner.digest())
+    }
+}
+
+impl core::Hash for SHA256D {
+    type Digest = Digest;
+
+    fn update<T>(&mut self, data: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.update(data);
+    }
+
+    fn reset(&mut self) {
+        self.reset();
+    }
+
+    fn digest(&self) -> Self::Digest {
+        self.digest()
+    }
+}
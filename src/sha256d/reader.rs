@@ -0,0 +1,40 @@
+//! This module is optional and can be enabled using the `reader` Cargo feature.
+//!
+//! The [`Reader`] allows on-the-fly calculation of the SHA-256d digest while reading the data.
+
+use std::io::Read;
+
+use chksum_reader as reader;
+#[cfg(feature = "async-runtime-tokio")]
+use tokio::io::AsyncRead;
+
+use super::SHA256D;
+
+/// A specialized [`Reader`](reader::Reader) type with the [`SHA256D`] hash algorithm.
+pub type Reader<R> = reader::Reader<R, SHA256D>;
+
+#[cfg(feature = "async-runtime-tokio")]
+/// A specialized [`AsyncReader`](reader::AsyncReader) type with the [`SHA256D`] hash algorithm.
+pub type AsyncReader<R> = reader::AsyncReader<R, SHA256D>;
+
+/// Creates new [`Reader`].
+pub fn new(inner: impl Read) -> Reader<impl Read> {
+    reader::new(inner)
+}
+
+/// Creates new [`Reader`] with provided hash.
+pub fn with_hash(inner: impl Read, hash: SHA256D) -> Reader<impl Read> {
+    reader::with_hash(inner, hash)
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+/// Creates new [`AsyncReader`].
+pub fn async_new(inner: impl AsyncRead) -> AsyncReader<impl AsyncRead> {
+    reader::async_new(inner)
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+/// Creates new [`AsyncReader`] with provided hash.
+pub fn async_with_hash(inner: impl AsyncRead, hash: SHA256D) -> AsyncReader<impl AsyncRead> {
+    reader::async_with_hash(inner, hash)
+}
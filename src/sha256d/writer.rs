@@ -0,0 +1,40 @@
+//! This module is optional and can be enabled using the `writer` Cargo feature.
+//!
+//! The [`Writer`] allows on-the-fly calculation of the SHA-256d digest while writing the data.
+
+use std::io::Write;
+
+use chksum_writer as writer;
+#[cfg(feature = "async-runtime-tokio")]
+use tokio::io::AsyncWrite;
+
+use super::SHA256D;
+
+/// A specialized [`Writer`](writer::Writer) type with the [`SHA256D`] hash algorithm.
+pub type Writer<W> = writer::Writer<W, SHA256D>;
+
+#[cfg(feature = "async-runtime-tokio")]
+/// A specialized [`AsyncWriter`](writer::AsyncWriter) type with the [`SHA256D`] hash algorithm.
+pub type AsyncWriter<W> = writer::AsyncWriter<W, SHA256D>;
+
+/// Creates new [`Writer`].
+pub fn new(inner: impl Write) -> Writer<impl Write> {
+    writer::new(inner)
+}
+
+/// Creates new [`Writer`] with provided hash.
+pub fn with_hash(inner: impl Write, hash: SHA256D) -> Writer<impl Write> {
+    writer::with_hash(inner, hash)
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+/// Creates new [`AsyncWriter`].
+pub fn async_new(inner: impl AsyncWrite) -> AsyncWriter<impl AsyncWrite> {
+    writer::async_new(inner)
+}
+
+#[cfg(feature = "async-runtime-tokio")]
+/// Creates new [`AsyncWriter`] with provided hash.
+pub fn async_with_hash(inner: impl AsyncWrite, hash: SHA256D) -> AsyncWriter<impl AsyncWrite> {
+    writer::async_with_hash(inner, hash)
+}
@@ -0,0 +1,179 @@
+//! This module is optional and can be enabled using the `sparse` Cargo feature.
+//!
+//! The [`chksum_sparse`] function calculates the digest of a sparse file, e.g. a virtual machine disk image, without
+//! reading its holes. Data regions are found with `SEEK_DATA` and `SEEK_HOLE`, and the zeros of the holes are fed into
+//! the hash from a static zero block instead of being read from the file. The digest is the same as the one of a full
+//! read.
+//!
+//! Holes are detected on Linux and Android only, and only when the file system supports it. Otherwise, and for special
+//! files, the whole file is read through a buffer. When the file is truncated while it is being hashed, an error is
+//! returned.
+//!
+//! # Enabling
+//!
+//! Add the following entry to your `Cargo.toml` file to enable the `sparse` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! chksum-sha2-256 = { version = "0.1.0", features = ["sparse"] }
+//! ```
+//!
+//! Alternatively, use the [`cargo add`](https://doc.rust-lang.org/cargo/commands/cargo-add.html) subcommand:
+//!
+//! ```shell
+//! cargo add chksum-sha2-256 --features sparse
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! # use std::path::Path;
+//! # use chksum_sha2_256::Result;
+//! use chksum_sha2_256 as sha2_256;
+//!
+//! # fn wrapper(path: &Path) -> Result<()> {
+//! let digest = sha2_256::chksum_sparse(path)?;
+//! assert_eq!(digest, sha2_256::chksum(path)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::{ChksumOptions, ChksumableWithOptions, Digest, Result, SHA2_256};
+
+/// The length of the static zero block fed into the hash for holes.
+const ZEROS_LENGTH: usize = 64 * 1024;
+
+/// The zero block fed into the hash for holes.
+static ZEROS: [u8; ZEROS_LENGTH] = [0; ZEROS_LENGTH];
+
+/// Computes the hash of the file at the given path, skipping the holes of sparse files.
+///
+/// Falls back to buffered reads when holes cannot be detected.
+///
+/// # Errors
+///
+/// Returns an error when the file cannot be opened or read, or when it is truncated during hashing.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256 as sha2_256;
+///
+/// # fn wrapper(path: &Path) -> Result<()> {
+/// let digest = sha2_256::chksum_sparse(path)?;
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn chksum_sparse(path: impl AsRef<Path>) -> Result<Digest> {
+    let mut file = File::open(path)?;
+    let mut hash = SHA2_256::new();
+    let mut options = ChksumOptions::new();
+
+    let metadata = file.metadata()?;
+    if !metadata.is_file() {
+        // pipes, devices and procfs entries have no holes to skip
+        file.chksum_with_options(&mut hash, &mut options)?;
+        return Ok(hash.digest());
+    }
+
+    let length = metadata.len();
+    let mut position = 0;
+    while position < length {
+        let Some(region) = data_region(&file, position, length)? else {
+            // holes cannot be detected, read the rest of the file
+            file.seek(SeekFrom::Start(position))?;
+            position += options.update((&mut file).take(length - position), &mut hash)?;
+            break;
+        };
+        update_zeros(&mut hash, region.start - position);
+        position = region.start;
+        if region.is_empty() {
+            break;
+        }
+        file.seek(SeekFrom::Start(region.start))?;
+        position += options.update((&mut file).take(region.end - region.start), &mut hash)?;
+        if position < region.end {
+            break;
+        }
+    }
+    ensure_length(&file, position, length)?;
+
+    Ok(hash.digest())
+}
+
+/// Feeds the given number of zero bytes into the hash.
+fn update_zeros(hash: &mut SHA2_256, mut length: u64) {
+    while length > 0 {
+        let chunk = length.min(ZEROS_LENGTH as u64);
+        #[allow(clippy::cast_possible_truncation)]
+        hash.update(&ZEROS[..chunk as usize]);
+        length -= chunk;
+    }
+}
+
+/// Returns an error when fewer bytes than the expected length were hashed.
+fn ensure_length(file: &File, position: u64, length: u64) -> io::Result<()> {
+    if position < length || file.metadata()?.len() < length {
+        let error = io::Error::new(ErrorKind::UnexpectedEof, "file was truncated during hashing");
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Returns the next data region at or after the given offset, limited to the given length.
+///
+/// Returns an empty region at the end of the file when only a hole follows, and `None` when holes cannot be detected.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn data_region(file: &File, offset: u64, length: u64) -> io::Result<Option<Range<u64>>> {
+    let start = match lseek(file, offset, libc::SEEK_DATA) {
+        Ok(start) => start.min(length),
+        // no data after the offset, the rest of the file is a hole
+        Err(error) if error.raw_os_error() == Some(libc::ENXIO) => length,
+        // the file system doesn't support hole detection
+        Err(error) if error.raw_os_error() == Some(libc::EINVAL) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    if start == length {
+        return Ok(Some(start..start));
+    }
+    let end = match lseek(file, start, libc::SEEK_HOLE) {
+        Ok(end) => end.min(length),
+        // the file was truncated, the missing bytes are detected while reading
+        Err(error) if error.raw_os_error() == Some(libc::ENXIO) => length,
+        Err(error) => return Err(error),
+    };
+    Ok(Some(start..end))
+}
+
+/// Returns the next data region at or after the given offset, limited to the given length.
+///
+/// Returns an empty region at the end of the file when only a hole follows, and `None` when holes cannot be detected.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+#[allow(clippy::unnecessary_wraps)]
+fn data_region(_file: &File, _offset: u64, _length: u64) -> io::Result<Option<Range<u64>>> {
+    Ok(None)
+}
+
+/// Repositions the offset of the file with the given `whence` directive.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn lseek(file: &File, offset: u64, whence: libc::c_int) -> io::Result<u64> {
+    use std::os::fd::AsRawFd;
+
+    let offset = libc::off64_t::try_from(offset).map_err(|error| io::Error::new(ErrorKind::InvalidInput, error))?;
+    // SAFETY: The file descriptor is owned by `file` and stays open for the duration of the call, and `lseek64` only
+    // changes its offset.
+    #[allow(unsafe_code)]
+    let result = unsafe { libc::lseek64(file.as_raw_fd(), offset, whence) };
+    u64::try_from(result).map_err(|_| io::Error::last_os_error())
+}
@@ -0,0 +1,78 @@
+//! The [`TaggedHash`] computes tagged hashes as defined by
+//! [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki), i.e.
+//! `SHA-256(SHA-256(tag) || SHA-256(tag) || data)`.
+//!
+//! The tag prefix is exactly one block long, so its state is computed once when the [`TaggedHash`] is created and
+//! each subsequent hash starts right after the first block.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chksum_sha2_256::TaggedHash;
+//!
+//! let challenge = TaggedHash::new("BIP0340/challenge");
+//! let digest = challenge.hash(b"message");
+//! assert_eq!(
+//!     digest.to_hex_lowercase(),
+//!     "f26c7f78a5e74cf7379558b9a0e390710b74d26ceaea5a5cbce3af592f35ecb1"
+//! );
+//! ```
+
+use crate::{Chksumable, Digest, Result, SHA2_256};
+
+/// A tagged hash with the precomputed state of the tag prefix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaggedHash {
+    prefix: SHA2_256,
+}
+
+impl TaggedHash {
+    /// Creates a new tagged hash for the given tag.
+    #[must_use]
+    pub fn new(tag: impl AsRef<[u8]>) -> Self {
+        let tag = SHA2_256::hash(tag);
+        let mut prefix = SHA2_256::new();
+        prefix.update(tag);
+        prefix.update(tag);
+        Self { prefix }
+    }
+
+    /// Returns a hash instance with the tag prefix already processed.
+    ///
+    /// Use it to hash the data incrementally.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chksum_sha2_256::TaggedHash;
+    ///
+    /// let challenge = TaggedHash::new("BIP0340/challenge");
+    /// let mut hash = challenge.hasher();
+    /// hash.update(b"mess");
+    /// hash.update(b"age");
+    /// assert_eq!(hash.digest(), challenge.hash(b"message"));
+    /// ```
+    #[must_use]
+    pub fn hasher(&self) -> SHA2_256 {
+        self.prefix.clone()
+    }
+
+    /// Computes the tagged hash of the given input.
+    #[must_use]
+    pub fn hash(&self, data: impl AsRef<[u8]>) -> Digest {
+        let mut hash = self.hasher();
+        hash.update(data);
+        hash.digest()
+    }
+
+    /// Computes the tagged hash of the given input, e.g. file or directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the input cannot be read.
+    pub fn chksum(&self, mut data: impl Chksumable) -> Result<Digest> {
+        let mut hash = self.hasher();
+        data.chksum_with(&mut hash)?;
+        Ok(hash.digest())
+    }
+}
@@ -0,0 +1,45 @@
+//! Uniquely named temporary files created next to their final destination, so they can be renamed into place.
+
+use std::ffi::{OsStr, OsString};
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The suffix of the temporary file names.
+pub(crate) const SUFFIX: &str = ".tmp";
+
+/// Creates a new uniquely named temporary file next to the given path, opened for reading and writing.
+///
+/// The name is `.<file name>.<process id>.<counter>.tmp`, so concurrent writers never share a temporary file.
+pub(crate) fn create(path: &Path) -> io::Result<(File, PathBuf)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "path doesn't name a file"))?;
+    loop {
+        let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut temporary = OsString::from(".");
+        temporary.push(name);
+        temporary.push(format!(".{}.{counter}{SUFFIX}", process::id()));
+        let temporary = path.with_file_name(temporary);
+        match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&temporary)
+        {
+            Ok(file) => return Ok((file, temporary)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Returns `true` when the file name is one of a temporary file created by [`create`].
+pub(crate) fn is_temporary(name: &OsStr) -> bool {
+    let name = name.as_encoded_bytes();
+    name.starts_with(b".") && name.ends_with(SUFFIX.as_bytes())
+}
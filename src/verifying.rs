@@ -0,0 +1,74 @@
+//! Reading of data verified against its expected digest and, optionally, size.
+
+use std::io::{self, Read};
+
+use crate::{verify_digest, verify_size, Digest, SHA2_256};
+
+/// A reader verifying the data against the expected digest and, optionally, size.
+///
+/// The data is hashed while it is read. An error with [`ErrorKind::InvalidData`](io::ErrorKind::InvalidData) is
+/// returned as soon as more data than the expected size is read, or instead of the end of the data when the size or
+/// the digest don't match.
+#[derive(Debug)]
+pub(crate) struct VerifyingReader<R> {
+    inner: R,
+    hash: SHA2_256,
+    expected: Digest,
+    size: Option<u64>,
+    read: u64,
+}
+
+impl<R> VerifyingReader<R>
+where
+    R: Read,
+{
+    /// Creates a reader verifying the data against the expected digest and size, if any.
+    pub(crate) fn new(inner: R, expected: Digest, size: Option<u64>) -> Self {
+        let hash = SHA2_256::new();
+        Self {
+            inner,
+            hash,
+            expected,
+            size,
+            read: 0,
+        }
+    }
+
+    /// Returns the expected digest.
+    #[cfg(feature = "cas")]
+    pub(crate) const fn expected(&self) -> Digest {
+        self.expected
+    }
+
+    /// Returns the number of bytes read so far.
+    #[cfg(feature = "reader")]
+    pub(crate) const fn bytes_read(&self) -> u64 {
+        self.read
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for VerifyingReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        self.read += length as u64;
+        if let Some(size) = self.size.filter(|&size| self.read > size) {
+            verify_size(size, self.read)?;
+        }
+        self.hash.update(&buf[..length]);
+        if length == 0 && !buf.is_empty() {
+            if let Some(size) = self.size {
+                verify_size(size, self.read)?;
+            }
+            verify_digest(self.expected, self.hash.digest())?;
+        }
+        Ok(length)
+    }
+}
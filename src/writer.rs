@@ -2,6 +2,9 @@
 //!
 //! The [`Writer`] allows on-the-fly calculation of the digest while writing the data.
 //!
+//! The [`AtomicWriter`] writes a file through a temporary file in the target directory and moves it into place only
+//! once the data is complete, synced to disk and optionally verified, so a crash never leaves a half-written file.
+//!
 //! # Enabling
 //!
 //! Add the following entry to your `Cargo.toml` file to enable the `writer` feature:
@@ -42,13 +45,15 @@
 //! # }
 //! ```
 
-use std::io::Write;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 use chksum_writer as writer;
 #[cfg(feature = "async-runtime-tokio")]
 use tokio::io::AsyncWrite;
 
-use crate::SHA2_256;
+use crate::{temporary, verify_digest, Digest, Result, SHA2_256};
 
 /// A specialized [`Writer`](writer::Writer) type with the [`SHA2_256`] hash algorithm.
 pub type Writer<W> = writer::Writer<W, SHA2_256>;
@@ -78,3 +83,161 @@ pub fn async_new(inner: impl AsyncWrite) -> AsyncWriter<impl AsyncWrite> {
 pub fn async_with_hash(inner: impl AsyncWrite, hash: SHA2_256) -> AsyncWriter<impl AsyncWrite> {
     writer::async_with_hash(inner, hash)
 }
+
+/// A file writer which commits the data atomically once it is complete.
+///
+/// The data is written to a temporary file created next to the target path and hashed on the fly. Calling
+/// [`commit`](AtomicWriter::commit) syncs the temporary file, checks the digest against the expected one (if any) and
+/// renames the file into place. A writer dropped without committing removes its temporary file, leaving the target
+/// path untouched.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::path::Path;
+/// use std::io::Write;
+///
+/// # use chksum_sha2_256::Result;
+/// use chksum_sha2_256::writer::AtomicWriter;
+///
+/// # fn wrapper(path: &Path) -> Result<()> {
+/// let mut writer = AtomicWriter::create(path)?;
+/// writer.write_all(b"example data")?;
+/// let digest = writer.commit()?;
+/// assert_eq!(
+///     digest.to_hex_lowercase(),
+///     "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AtomicWriter {
+    file: File,
+    path: PathBuf,
+    temporary: PathBuf,
+    hash: SHA2_256,
+    expected: Option<Digest>,
+    committed: bool,
+}
+
+impl AtomicWriter {
+    /// Creates new [`AtomicWriter`] for the given target path.
+    ///
+    /// The temporary file is created in the directory of the target path, so the final rename never crosses
+    /// filesystems.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the temporary file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (file, temporary) = temporary::create(&path)?;
+        Ok(Self {
+            file,
+            path,
+            temporary,
+            hash: SHA2_256::new(),
+            expected: None,
+            committed: false,
+        })
+    }
+
+    /// Sets the digest the written data is expected to have.
+    ///
+    /// When set, [`commit`](AtomicWriter::commit) refuses to move the file into place if the digests differ.
+    #[must_use]
+    pub fn with_expected(mut self, digest: Digest) -> Self {
+        self.expected = Some(digest);
+        self
+    }
+
+    /// Returns the target path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the digest of the data written so far.
+    #[must_use]
+    pub fn digest(&self) -> Digest {
+        self.hash.digest()
+    }
+
+    /// Syncs the data, verifies the digest and atomically moves the file to the target path.
+    ///
+    /// Returns the digest of the written data. On failure the temporary file is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with [`ErrorKind::InvalidData`](io::ErrorKind::InvalidData) when the digest doesn't match the
+    /// expected one, or any error from syncing or renaming the file.
+    pub fn commit(self) -> Result<Digest> {
+        let path = self.path.clone();
+        self.commit_to(&path)
+    }
+
+    /// Syncs the data, verifies the digest and atomically moves the file to the given path.
+    ///
+    /// The path must be on the same filesystem as the target path the writer was created for.
+    pub(crate) fn commit_to(mut self, path: &Path) -> Result<Digest> {
+        self.file.flush()?;
+        self.file.sync_all()?;
+        let digest = self.hash.digest();
+        if let Some(expected) = self.expected {
+            verify_digest(expected, digest)?;
+        }
+        fs::rename(&self.temporary, path)?;
+        self.committed = true;
+        sync_parent(path)?;
+        Ok(digest)
+    }
+
+    /// Discards the written data and removes the temporary file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the temporary file cannot be removed.
+    pub fn abort(mut self) -> Result<()> {
+        self.committed = true;
+        fs::remove_file(&self.temporary)?;
+        Ok(())
+    }
+}
+
+impl Write for AtomicWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let length = self.file.write(buf)?;
+        self.hash.update(&buf[..length]);
+        Ok(length)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for AtomicWriter {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.temporary);
+        }
+    }
+}
+
+/// Syncs the directory containing the given path, so the rename survives a crash.
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Syncs the directory containing the given path, so the rename survives a crash.
+///
+/// Directories cannot be synced on this platform, the rename is durable once the filesystem flushes its metadata.
+#[cfg(not(unix))]
+fn sync_parent(_: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,340 @@
+#[cfg(feature = "archive")]
+use std::fs;
+#[cfg(feature = "archive")]
+use std::io::{Cursor, ErrorKind, Write};
+#[cfg(feature = "archive")]
+use std::path::Path;
+
+#[cfg(feature = "archive")]
+use assert_fs::prelude::{FileWriteBin, PathChild, PathCreateDir};
+#[cfg(feature = "archive")]
+use assert_fs::TempDir;
+#[cfg(feature = "archive")]
+use chksum_sha2_256::archive::{self, Format};
+#[cfg(feature = "archive")]
+use chksum_sha2_256::{chksum, chksum_with, ChksumOptions, Error};
+#[cfg(feature = "archive")]
+use tar::{Builder, EntryType, Header};
+#[cfg(feature = "archive")]
+use zip::write::SimpleFileOptions;
+#[cfg(feature = "archive")]
+use zip::ZipWriter;
+
+type BoxError = Box<dyn std::error::Error>;
+
+#[cfg(feature = "archive")]
+fn error_kind(error: Error) -> Option<ErrorKind> {
+    match error {
+        Error::Io(error) => Some(error.kind()),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "archive")]
+fn append(builder: &mut Builder<Vec<u8>>, kind: EntryType, path: &str, mode: u32, data: &[u8]) -> std::io::Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(kind);
+    header.set_mode(mode);
+    header.set_size(data.len() as u64);
+    builder.append_data(&mut header, path, data)
+}
+
+#[cfg(feature = "archive")]
+fn append_link(builder: &mut Builder<Vec<u8>>, kind: EntryType, path: &str, target: &str) -> std::io::Result<()> {
+    let mut header = Header::new_gnu();
+    header.set_entry_type(kind);
+    header.set_mode(0o777);
+    header.set_size(0);
+    builder.append_link(&mut header, path, target)
+}
+
+/// Builds a tar archive of the tree created by [`create_tree`], in a different order than sorted.
+#[cfg(feature = "archive")]
+fn create_tar() -> std::io::Result<Vec<u8>> {
+    let mut builder = Builder::new(Vec::new());
+    append(&mut builder, EntryType::Regular, "dir/nested/file", 0o644, b"data")?;
+    append(&mut builder, EntryType::Regular, "./b", 0o755, b"example data")?;
+    append(&mut builder, EntryType::Directory, "dir/", 0o755, b"")?;
+    append(&mut builder, EntryType::Regular, "a", 0o644, b"data")?;
+    builder.into_inner()
+}
+
+/// Builds a zip archive of the tree created by [`create_tree`].
+#[cfg(feature = "archive")]
+fn create_zip() -> zip::result::ZipResult<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().unix_permissions(0o755);
+    writer.start_file("b", options)?;
+    writer.write_all(b"example data")?;
+    writer.add_directory("dir/", options)?;
+    writer.add_directory("dir/nested/", options)?;
+    let options = SimpleFileOptions::default().unix_permissions(0o644);
+    writer.start_file("dir/nested/file", options)?;
+    writer.write_all(b"data")?;
+    writer.start_file("a", options)?;
+    writer.write_all(b"data")?;
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Creates the extracted tree of the archives.
+#[cfg(feature = "archive")]
+fn create_tree(root: &Path) -> Result<(), BoxError> {
+    let temp_dir = assert_fs::fixture::ChildPath::new(root);
+    temp_dir.child("a").write_binary(b"data")?;
+    temp_dir.child("b").write_binary(b"example data")?;
+    temp_dir.child("dir/nested").create_dir_all()?;
+    temp_dir.child("dir/nested/file").write_binary(b"data")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        for path in ["a", "dir/nested/file"] {
+            fs::set_permissions(root.join(path), fs::Permissions::from_mode(0o644))?;
+        }
+        for path in ["b", "dir", "dir/nested"] {
+            fs::set_permissions(root.join(path), fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn archive_format_detect() {
+    #[cfg(feature = "archive")]
+    {
+        assert_eq!(Format::detect(&[0x1F, 0x8B, 0x08]), Format::TarGzip);
+        assert_eq!(Format::detect(&[0x28, 0xB5, 0x2F, 0xFD]), Format::TarZstd);
+        assert_eq!(Format::detect(b"PK\x03\x04"), Format::Zip);
+        assert_eq!(Format::detect(b"PK\x05\x06"), Format::Zip);
+        assert_eq!(Format::detect(b"file"), Format::Tar);
+        assert_eq!(Format::detect(b""), Format::Tar);
+    }
+}
+
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_tar_matches_directory() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        let temp_dir = TempDir::new()?;
+        create_tree(&temp_dir.join("tree"))?;
+        let expected = chksum(temp_dir.join("tree"))?;
+
+        let tar = create_tar()?;
+        fs::write(temp_dir.join("archive.tar"), &tar)?;
+        assert_eq!(archive::chksum(temp_dir.join("archive.tar"))?, expected);
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar)?;
+        fs::write(temp_dir.join("archive.tar.gz"), encoder.finish()?)?;
+        assert_eq!(archive::chksum(temp_dir.join("archive.tar.gz"))?, expected);
+
+        let zstd = zstd::encode_all(&tar[..], 0)?;
+        fs::write(temp_dir.join("archive.tar.zst"), &zstd)?;
+        assert_eq!(archive::chksum(temp_dir.join("archive.tar.zst"))?, expected);
+
+        let digest = archive::chksum_reader(&zstd[..], Format::TarZstd, &mut ChksumOptions::new())?;
+        assert_eq!(digest, expected);
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_multi_member_gzip() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        let tar = create_tar()?;
+        let gzip = |data: &[u8]| -> std::io::Result<Vec<u8>> {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        };
+        let expected = archive::chksum_reader(&gzip(&tar)?[..], Format::TarGzip, &mut ChksumOptions::new())?;
+
+        // block compressors split the stream at arbitrary offsets, including entry boundaries
+        for split in [700, 1024] {
+            let mut members = gzip(&tar[..split])?;
+            members.extend(gzip(&tar[split..])?);
+            let digest = archive::chksum_reader(&members[..], Format::TarGzip, &mut ChksumOptions::new())?;
+            assert_eq!(digest, expected);
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_zip_matches_directory() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        let temp_dir = TempDir::new()?;
+        create_tree(&temp_dir.join("tree"))?;
+        let expected = chksum(temp_dir.join("tree"))?;
+
+        let zip = create_zip()?;
+        fs::write(temp_dir.join("archive.zip"), &zip)?;
+        assert_eq!(archive::chksum(temp_dir.join("archive.zip"))?, expected);
+
+        let digest = archive::chksum_reader(&zip[..], Format::Zip, &mut ChksumOptions::new())?;
+        assert_eq!(digest, expected);
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_large_files_match_directory() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        // contents larger than the read buffer, archived in reverse order of hashing
+        let files: Vec<(String, Vec<u8>)> = (0..4u8)
+            .rev()
+            .map(|index| (format!("file{index}"), vec![index; 100_000 + usize::from(index) * 7]))
+            .collect();
+
+        let temp_dir = TempDir::new()?;
+        let mut builder = Builder::new(Vec::new());
+        for (name, data) in &files {
+            temp_dir.child("tree").child(name).write_binary(data)?;
+            append(&mut builder, EntryType::Regular, name, 0o644, data)?;
+        }
+        let tar = builder.into_inner()?;
+
+        let expected = chksum(temp_dir.join("tree"))?;
+        let digest = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new())?;
+        assert_eq!(digest, expected);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_metadata_matches_directory() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        let temp_dir = TempDir::new()?;
+        create_tree(&temp_dir.join("tree"))?;
+        let mut options = ChksumOptions::new().include_permissions(true).include_executable(true);
+        let expected = chksum_with(temp_dir.join("tree"), &mut options)?;
+        assert_ne!(expected, chksum(temp_dir.join("tree"))?);
+
+        fs::write(temp_dir.join("archive.tar"), create_tar()?)?;
+        assert_eq!(
+            archive::chksum_with(temp_dir.join("archive.tar"), &mut options)?,
+            expected
+        );
+
+        fs::write(temp_dir.join("archive.zip"), create_zip()?)?;
+        assert_eq!(
+            archive::chksum_with(temp_dir.join("archive.zip"), &mut options)?,
+            expected
+        );
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_links() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.join("tree");
+        create_tree(&root)?;
+        symlink("dir/nested", root.join("link"))?;
+        symlink("../../a", root.join("dir/nested/up"))?;
+        fs::hard_link(root.join("b"), root.join("hard"))?;
+
+        let mut builder = Builder::new(Vec::new());
+        append_link(&mut builder, EntryType::Symlink, "link", "dir/nested")?;
+        append_link(&mut builder, EntryType::Symlink, "dir/nested/up", "../../a")?;
+        append_link(&mut builder, EntryType::Link, "hard", "b")?;
+        let tree = create_tar()?;
+        let mut archive = tar::Archive::new(&tree[..]);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let mut header = entry.header().clone();
+            let path = entry.path()?.into_owned();
+            builder.append_data(&mut header, path, &mut entry)?;
+        }
+        let tar = builder.into_inner()?;
+        fs::write(temp_dir.join("archive.tar"), &tar)?;
+
+        assert_eq!(archive::chksum(temp_dir.join("archive.tar"))?, chksum(&root)?);
+
+        let mut options = ChksumOptions::new()
+            .include_permissions(true)
+            .include_symlink_targets(true);
+        let expected = chksum_with(&root, &mut options)?;
+        assert_eq!(
+            archive::chksum_with(temp_dir.join("archive.tar"), &mut options)?,
+            expected
+        );
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "archive"), ignore)]
+#[test]
+fn archive_invalid_paths() -> Result<(), BoxError> {
+    #[cfg(feature = "archive")]
+    {
+        let mut builder = Builder::new(Vec::new());
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Regular);
+        header.set_size(4);
+        header.set_mode(0o644);
+        header.as_old_mut().name[..7].copy_from_slice(b"../file");
+        header.set_cksum();
+        builder.append(&header, &b"data"[..])?;
+        let tar = builder.into_inner()?;
+        let error = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::InvalidData));
+
+        let mut builder = Builder::new(Vec::new());
+        append_link(&mut builder, EntryType::Symlink, "link", "../outside")?;
+        let tar = builder.into_inner()?;
+        let error = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::InvalidData));
+
+        let mut builder = Builder::new(Vec::new());
+        append_link(&mut builder, EntryType::Symlink, "link", "missing")?;
+        let tar = builder.into_inner()?;
+        let error = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::NotFound));
+
+        let mut builder = Builder::new(Vec::new());
+        append_link(&mut builder, EntryType::Symlink, "a", "b")?;
+        append_link(&mut builder, EntryType::Symlink, "b", "a")?;
+        let tar = builder.into_inner()?;
+        let error = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::InvalidData));
+
+        // links to ancestor directories are rejected instead of being followed at every level
+        let mut builder = Builder::new(Vec::new());
+        append(&mut builder, EntryType::Directory, "a", 0o755, b"")?;
+        append_link(&mut builder, EntryType::Symlink, "a/l", "..")?;
+        append_link(&mut builder, EntryType::Symlink, "a/m", "..")?;
+        let tar = builder.into_inner()?;
+        let error = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::InvalidData));
+
+        let mut builder = Builder::new(Vec::new());
+        append(&mut builder, EntryType::Directory, "a", 0o755, b"")?;
+        append(&mut builder, EntryType::Directory, "b", 0o755, b"")?;
+        append_link(&mut builder, EntryType::Symlink, "a/b", "../b")?;
+        append_link(&mut builder, EntryType::Symlink, "b/a", "../a")?;
+        let tar = builder.into_inner()?;
+        let error = archive::chksum_reader(&tar[..], Format::Tar, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::InvalidData));
+
+        let error = archive::chksum_reader(&b"PK\x03\x04"[..], Format::Zip, &mut ChksumOptions::new()).unwrap_err();
+        assert_eq!(error_kind(error), Some(ErrorKind::InvalidData));
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "hw-accel")]
+use chksum_sha2_256::backend::{self, Backend};
+#[cfg(feature = "hw-accel")]
+use chksum_sha2_256::{hash, SHA2_256};
+
+#[cfg_attr(not(feature = "hw-accel"), ignore)]
+#[test]
+fn force_portable() {
+    #[cfg(feature = "hw-accel")]
+    {
+        backend::force_portable(true);
+        assert_eq!(backend::active(), Backend::Portable);
+        let hash = SHA2_256::new();
+
+        backend::force_portable(false);
+        assert_eq!(backend::active(), backend::detected());
+        // existing hashes keep their backend
+        assert_eq!(hash.backend(), Backend::Portable);
+    }
+}
+
+#[cfg_attr(not(feature = "hw-accel"), ignore)]
+#[test]
+fn with_backend() {
+    #[cfg(feature = "hw-accel")]
+    {
+        assert!(Backend::Portable.is_supported());
+        assert!(backend::detected().is_supported());
+
+        let mut hash = SHA2_256::with_backend(Backend::Portable);
+        hash.update(b"example data");
+        hash.reset();
+        assert_eq!(hash.backend(), Backend::Portable);
+        assert_eq!(
+            hash.digest().to_hex_lowercase(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
+
+#[cfg_attr(not(feature = "hw-accel"), ignore)]
+#[test]
+fn backends_match_reference() {
+    #[cfg(feature = "hw-accel")]
+    {
+        let data: Vec<u8> = (0..=u8::MAX).cycle().take(1000).collect();
+
+        for backend in [Backend::Portable, backend::detected()] {
+            for length in 0..data.len() {
+                let data = &data[..length];
+                let expected = hash::hash(data).to_hex_lowercase();
+
+                let mut hash = SHA2_256::with_backend(backend);
+                hash.update(data);
+                assert_eq!(
+                    hash.digest().to_hex_lowercase(),
+                    expected,
+                    "{backend} backend, length {length}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "hw-accel"), ignore)]
+#[test]
+fn backends_match_reference_with_split_updates() {
+    #[cfg(feature = "hw-accel")]
+    {
+        let data: Vec<u8> = (0..=u8::MAX).cycle().take(300).collect();
+        let expected = hash::hash(&data).to_hex_lowercase();
+
+        for backend in [Backend::Portable, backend::detected()] {
+            for split in [1, 3, 63, 64, 65, 127, 128, 200] {
+                let mut hash = SHA2_256::with_backend(backend);
+                for chunk in data.chunks(split) {
+                    hash.update(chunk);
+                }
+                assert_eq!(
+                    hash.digest().to_hex_lowercase(),
+                    expected,
+                    "{backend} backend, split {split}"
+                );
+            }
+        }
+    }
+}
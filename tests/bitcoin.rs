@@ -0,0 +1,84 @@
+#[cfg(feature = "reader")]
+use std::io::Read;
+#[cfg(feature = "writer")]
+use std::io::Write;
+
+use chksum_sha2_256::sha256d::{self, SHA256D};
+use chksum_sha2_256::{Result, TaggedHash, SHA2_256};
+
+#[test]
+fn sha256d_hash() -> Result<()> {
+    let expected = "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50";
+    assert_eq!(sha256d::hash(b"hello").to_hex_lowercase(), expected);
+    assert_eq!(SHA256D::hash(b"hello").to_hex_lowercase(), expected);
+    assert_eq!(sha256d::chksum(b"hello")?.to_hex_lowercase(), expected);
+
+    let mut hash = sha256d::new();
+    hash.update(b"he");
+    hash.update(b"llo");
+    assert_eq!(hash.digest().to_hex_lowercase(), expected);
+
+    hash.reset();
+    assert_eq!(hash.digest(), SHA2_256::hash(SHA2_256::hash(b"")));
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "reader"), ignore)]
+#[test]
+fn sha256d_reader() -> Result<()> {
+    #[cfg(feature = "reader")]
+    {
+        let mut reader = sha256d::reader::new(&b"hello"[..]);
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer)?;
+        assert_eq!(buffer, b"hello");
+        assert_eq!(reader.digest(), sha256d::hash(b"hello"));
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "writer"), ignore)]
+#[test]
+fn sha256d_writer() -> Result<()> {
+    #[cfg(feature = "writer")]
+    {
+        let mut writer = sha256d::writer::new(Vec::new());
+        writer.write_all(b"hello")?;
+        assert_eq!(writer.digest(), sha256d::hash(b"hello"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tagged_hash() -> Result<()> {
+    let challenge = TaggedHash::new("BIP0340/challenge");
+    assert_eq!(
+        challenge.hash(b"message").to_hex_lowercase(),
+        "f26c7f78a5e74cf7379558b9a0e390710b74d26ceaea5a5cbce3af592f35ecb1"
+    );
+    assert_eq!(challenge.chksum(b"message")?, challenge.hash(b"message"));
+
+    let leaf = TaggedHash::new(b"TapLeaf");
+    assert_eq!(
+        leaf.hash(b"").to_hex_lowercase(),
+        "5212c288a377d1f8164962a5a13429f9ba6a7b84e59776a52c6637df2106facb"
+    );
+
+    // the cached prefix must not be affected by previous hashes
+    let data: Vec<u8> = (0..=u8::MAX).collect();
+    let first = leaf.hash(&data);
+    let second = leaf.hash(&data);
+    assert_eq!(first, second);
+
+    let tag = SHA2_256::hash(b"TapLeaf");
+    let mut hash = SHA2_256::new();
+    hash.update(tag);
+    hash.update(tag);
+    hash.update(&data);
+    assert_eq!(first, hash.digest());
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+#[cfg(feature = "bytes")]
+use bytes::{Buf, Bytes, BytesMut};
+use chksum_sha2_256::Result;
+#[cfg(feature = "bytes")]
+use chksum_sha2_256::{chksum, chksum_with, hash_buf, ChksumOptions, SHA2_256};
+
+#[cfg(feature = "bytes")]
+const DIGEST: &str = "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061";
+
+#[cfg_attr(not(feature = "bytes"), ignore)]
+#[test]
+fn hash_bytes() -> Result<()> {
+    #[cfg(feature = "bytes")]
+    {
+        let bytes = Bytes::from_static(b"example data");
+        assert_eq!(hash_buf(bytes.clone()).to_hex_lowercase(), DIGEST);
+        assert_eq!(SHA2_256::hash(&bytes).to_hex_lowercase(), DIGEST);
+
+        let mut options = ChksumOptions::new();
+        assert_eq!(chksum_with(&bytes, &mut options)?.to_hex_lowercase(), DIGEST);
+        assert_eq!(chksum_with(bytes, &mut options)?.to_hex_lowercase(), DIGEST);
+
+        let bytes_mut = BytesMut::from(&b"example data"[..]);
+        assert_eq!(chksum_with(&bytes_mut, &mut options)?.to_hex_lowercase(), DIGEST);
+        assert_eq!(chksum(&bytes_mut[..])?.to_hex_lowercase(), DIGEST);
+        assert_eq!(hash_buf(bytes_mut).to_hex_lowercase(), DIGEST);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "bytes"), ignore)]
+#[test]
+fn hash_chained_buf() {
+    #[cfg(feature = "bytes")]
+    {
+        let data = Bytes::from_static(b"exa")
+            .chain(&b"mple "[..])
+            .chain(BytesMut::from(&b"data"[..]));
+        assert_eq!(hash_buf(data).to_hex_lowercase(), DIGEST);
+
+        let mut data = Bytes::from_static(b"skipped example data");
+        data.advance(8);
+        let mut hash = SHA2_256::new();
+        hash.update_buf(&mut data);
+        assert!(!data.has_remaining());
+        assert_eq!(hash.digest().to_hex_lowercase(), DIGEST);
+
+        assert_eq!(hash_buf(Bytes::new()), SHA2_256::hash(b""));
+    }
+}
@@ -0,0 +1,160 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::thread::sleep;
+use std::time::Duration;
+
+use assert_fs::prelude::{FileWriteBin, PathChild};
+use assert_fs::TempDir;
+use chksum_sha2_256::cache::DigestCache;
+use chksum_sha2_256::{chksum, Error, SHA2_256};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// Waits until timestamps of files written so far are older than the current second.
+fn wait_for_clean_timestamps() {
+    sleep(Duration::from_millis(1100));
+}
+
+#[test]
+fn cache_hit_and_change() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.child("file");
+    file.write_binary(b"data")?;
+    wait_for_clean_timestamps();
+
+    let mut cache = DigestCache::new();
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&file)?, None);
+    assert_eq!(cache.chksum(&file)?, SHA2_256::hash(b"data"));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&file)?, Some(SHA2_256::hash(b"data")));
+    assert_eq!(cache.chksum(&file)?, SHA2_256::hash(b"data"));
+
+    file.write_binary(b"example data")?;
+    assert_eq!(cache.get(&file)?, None);
+    assert_eq!(cache.chksum(&file)?, SHA2_256::hash(b"example data"));
+
+    assert!(cache.invalidate(&file));
+    assert!(!cache.invalidate(&file));
+    assert!(cache.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn cache_racy_entry() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.child("file");
+    file.write_binary(b"data")?;
+
+    // the file was modified within the same second it is hashed in
+    let mut cache = DigestCache::new();
+    assert_eq!(cache.chksum(&file)?, SHA2_256::hash(b"data"));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&file)?, None);
+
+    // same size and likely the same timestamps, only detected because the entry is racy
+    file.write_binary(b"atad")?;
+    assert_eq!(cache.chksum(&file)?, SHA2_256::hash(b"atad"));
+
+    Ok(())
+}
+
+#[test]
+fn cache_directory_is_not_cached() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("file").write_binary(b"data")?;
+
+    let mut cache = DigestCache::new();
+    assert_eq!(cache.chksum(&temp_dir)?, chksum(temp_dir.path())?);
+    assert!(cache.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn cache_save_and_load() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let cache_file = temp_dir.child("cache");
+    let first = temp_dir.child("first");
+    first.write_binary(b"data")?;
+    let second = temp_dir.child("with space\nand line feed\\");
+    second.write_binary(b"example data")?;
+    wait_for_clean_timestamps();
+
+    assert!(DigestCache::load(&cache_file)?.is_empty());
+
+    let mut cache = DigestCache::new();
+    cache.chksum(&first)?;
+    cache.chksum(&second)?;
+    cache.save(&cache_file)?;
+    let saved = fs::read_to_string(&cache_file)?;
+    assert!(saved.starts_with("chksum-sha2-256 digest cache v1\n"));
+    assert_eq!(saved.lines().count(), 3);
+
+    let loaded = DigestCache::load(&cache_file)?;
+    assert_eq!(loaded, cache);
+    assert_eq!(loaded.get(&second)?, Some(SHA2_256::hash(b"example data")));
+
+    cache.save(&cache_file)?;
+    assert_eq!(fs::read_to_string(&cache_file)?, saved);
+
+    fs::write(&cache_file, "not a cache\n")?;
+    let error = DigestCache::load(&cache_file).unwrap_err();
+    assert!(matches!(error, Error::Io(error) if error.kind() == ErrorKind::InvalidData));
+
+    Ok(())
+}
+
+#[test]
+fn cache_save_temporary_extension() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let cache_file = temp_dir.child("cache.tmp");
+    let file = temp_dir.child("file");
+    file.write_binary(b"data")?;
+    wait_for_clean_timestamps();
+
+    let mut cache = DigestCache::new();
+    cache.chksum(&file)?;
+    cache.save(&cache_file)?;
+    cache.save(&cache_file)?;
+    assert_eq!(DigestCache::load(&cache_file)?, cache);
+
+    let mut names: Vec<_> = fs::read_dir(&temp_dir)?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .collect::<Result<_, _>>()?;
+    names.sort();
+    assert_eq!(names, ["cache.tmp", "file"]);
+
+    Ok(())
+}
+
+#[test]
+fn cache_prune() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let kept = temp_dir.child("kept");
+    kept.write_binary(b"data")?;
+    let removed = temp_dir.child("removed");
+    removed.write_binary(b"data")?;
+    let changed = temp_dir.child("changed");
+    changed.write_binary(b"data")?;
+
+    let mut cache = DigestCache::new();
+    cache.chksum(&kept)?;
+    cache.chksum(&removed)?;
+    cache.chksum(&changed)?;
+    fs::remove_file(&removed)?;
+    changed.write_binary(b"example data")?;
+
+    assert_eq!(cache.prune(), 2);
+    assert_eq!(cache.len(), 1);
+
+    cache.retain(|path| path != kept.path());
+    assert!(cache.is_empty());
+
+    cache.chksum(&kept)?;
+    cache.clear();
+    assert!(cache.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+#[cfg(feature = "cas")]
+use std::collections::HashSet;
+#[cfg(feature = "cas")]
+use std::fs;
+#[cfg(feature = "cas")]
+use std::io::{ErrorKind, Read, Write};
+#[cfg(feature = "cas")]
+use std::time::Duration;
+
+#[cfg(feature = "cas")]
+use assert_fs::TempDir;
+#[cfg(feature = "cas")]
+use chksum_sha2_256::cas::{BlobReader, Store};
+#[cfg(feature = "cas")]
+use chksum_sha2_256::{Error, SHA2_256};
+
+type BoxError = Box<dyn std::error::Error>;
+
+#[cfg(feature = "cas")]
+fn error_kind(error: Error) -> Option<ErrorKind> {
+    match error {
+        Error::Io(error) => Some(error.kind()),
+        _ => None,
+    }
+}
+
+#[cfg_attr(not(feature = "cas"), ignore)]
+#[test]
+fn cas_insert() -> Result<(), BoxError> {
+    #[cfg(feature = "cas")]
+    {
+        let temp_dir = TempDir::new()?;
+        let store = Store::open(temp_dir.join("store"))?;
+        assert_eq!(store.root(), temp_dir.join("store"));
+
+        let digest = store.insert(b"data")?;
+        assert_eq!(
+            digest.to_hex_lowercase(),
+            "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7"
+        );
+        let path = store.path(&digest);
+        assert_eq!(
+            path,
+            temp_dir
+                .join("store")
+                .join("3a")
+                .join("6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7")
+        );
+        assert_eq!(fs::read(&path)?, b"data");
+        assert!(store.contains(&digest));
+
+        assert_eq!(store.insert(b"data")?, digest);
+        assert_eq!(store.insert_reader(&b"data"[..])?, digest);
+
+        let mut writer = store.writer()?;
+        writer.write_all(b"example ")?;
+        writer.write_all(b"data")?;
+        let other = writer.commit()?;
+        assert_eq!(other, SHA2_256::hash(b"example data"));
+
+        let mut expected = vec![digest, other];
+        expected.sort();
+        assert_eq!(store.digests()?, expected);
+        assert_eq!(fs::read_dir(store.root().join("tmp"))?.count(), 0);
+
+        let writer = store.writer()?;
+        drop(writer);
+        assert_eq!(fs::read_dir(store.root().join("tmp"))?.count(), 0);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "cas"), ignore)]
+#[test]
+fn cas_verify_on_read() -> Result<(), BoxError> {
+    #[cfg(feature = "cas")]
+    {
+        let temp_dir = TempDir::new()?;
+        let store = Store::open(&temp_dir)?;
+        let digest = store.insert(b"data")?;
+        assert_eq!(store.read(&digest)?, b"data");
+        store.verify(&digest)?;
+
+        fs::write(store.path(&digest), b"tampered")?;
+        assert_eq!(
+            error_kind(store.read(&digest).unwrap_err()),
+            Some(ErrorKind::InvalidData)
+        );
+        assert_eq!(
+            error_kind(store.verify(&digest).unwrap_err()),
+            Some(ErrorKind::InvalidData)
+        );
+
+        let missing = SHA2_256::hash(b"missing");
+        assert!(!store.contains(&missing));
+        assert_eq!(error_kind(store.read(&missing).unwrap_err()), Some(ErrorKind::NotFound));
+
+        let mut reader = BlobReader::new(&b"data"[..], digest);
+        assert_eq!(reader.expected(), digest);
+        let mut data = String::new();
+        reader.read_to_string(&mut data)?;
+        assert_eq!(data, "data");
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "cas"), ignore)]
+#[test]
+fn cas_gc() -> Result<(), BoxError> {
+    #[cfg(feature = "cas")]
+    {
+        let temp_dir = TempDir::new()?;
+        let store = Store::open(&temp_dir)?;
+        let kept = store.insert(b"data")?;
+        let dropped = store.insert(b"example data")?;
+        fs::write(temp_dir.join("README"), b"not a blob")?;
+
+        let reachable = HashSet::from([kept]);
+        assert_eq!(store.gc(&reachable)?, [dropped]);
+        assert_eq!(store.digests()?, [kept]);
+        assert!(!store.path(&dropped).parent().unwrap().exists());
+        assert!(temp_dir.join("README").exists());
+
+        // fresh temporary files may belong to inserts in progress
+        fs::write(temp_dir.join("tmp/.blob.leftover.tmp"), b"partial")?;
+        store.gc(&reachable)?;
+        assert!(temp_dir.join("tmp/.blob.leftover.tmp").exists());
+        assert_eq!(store.remove_temporary(Duration::ZERO)?, 1);
+        assert!(!temp_dir.join("tmp/.blob.leftover.tmp").exists());
+
+        assert!(store.remove(&kept)?);
+        assert!(!store.remove(&kept)?);
+        assert!(store.digests()?.is_empty());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::io::{self, Read};
+
+#[cfg(feature = "async-runtime-tokio")]
+use chksum_sha2_256::cdc::AsyncChunker;
+use chksum_sha2_256::cdc::{Chunk, Chunker, ChunkerOptions};
+use chksum_sha2_256::{Digest, Result, SHA2_256};
+
+/// Generates deterministic pseudo-random data.
+fn data(length: usize, seed: u64) -> Vec<u8> {
+    let mut state = seed;
+    (0..length)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 56) as u8
+        })
+        .collect()
+}
+
+/// A reader returning at most one byte per read.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = buf.len().min(self.0.len()).min(1);
+        buf[..length].copy_from_slice(&self.0[..length]);
+        self.0 = &self.0[length..];
+        Ok(length)
+    }
+}
+
+fn chunks(data: impl Read, options: ChunkerOptions) -> Result<Vec<Chunk>> {
+    Chunker::with_options(data, options).collect()
+}
+
+/// The chunk lengths of `data(128 * 1024, 42)`, pinned to detect changes of the boundaries.
+const LENGTHS: [usize; 14] = [
+    11_255, 6_913, 10_961, 8_612, 9_608, 9_306, 8_759, 11_058, 10_360, 3_730, 8_416, 14_647, 9_194, 8_253,
+];
+
+const OPTIONS: ChunkerOptions = ChunkerOptions::new(2 * 1024, 8 * 1024, 32 * 1024);
+
+#[test]
+fn cdc_options() {
+    let options = ChunkerOptions::default();
+    assert_eq!(options.min_size(), 16 * 1024);
+    assert_eq!(options.avg_size(), 64 * 1024);
+    assert_eq!(options.max_size(), 256 * 1024);
+
+    assert_eq!(OPTIONS.min_size(), 2 * 1024);
+    assert_eq!(OPTIONS.avg_size(), 8 * 1024);
+    assert_eq!(OPTIONS.max_size(), 32 * 1024);
+}
+
+#[test]
+#[should_panic(expected = "chunk sizes must satisfy minimum <= average <= maximum")]
+fn cdc_options_unordered() {
+    let _ = ChunkerOptions::new(4, 2, 8);
+}
+
+#[test]
+fn cdc_chunks() -> Result<()> {
+    let data = data(1024 * 1024, 1);
+    let chunks = chunks(&data[..], OPTIONS)?;
+
+    let mut offset = 0;
+    for (index, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.offset(), offset as u64);
+        assert!(chunk.length() <= OPTIONS.max_size());
+        if index + 1 < chunks.len() {
+            assert!(chunk.length() >= OPTIONS.min_size());
+        }
+        let content = &data[offset..offset + chunk.length()];
+        assert_eq!(chunk.digest(), SHA2_256::hash(content));
+        offset += chunk.length();
+    }
+    assert_eq!(offset, data.len());
+
+    let average = data.len() / chunks.len();
+    assert!((4 * 1024..16 * 1024).contains(&average), "average chunk size {average}");
+
+    let (offset, length, digest): (u64, usize, Digest) = chunks[1].into();
+    assert_eq!(
+        (offset, length, digest),
+        (chunks[1].offset(), chunks[1].length(), chunks[1].digest())
+    );
+    Ok(())
+}
+
+#[test]
+fn cdc_deterministic_boundaries() -> Result<()> {
+    let data = data(128 * 1024, 42);
+    let lengths: Vec<usize> = chunks(&data[..], OPTIONS)?.iter().map(Chunk::length).collect();
+    assert_eq!(lengths, LENGTHS);
+
+    let chunks = chunks(&data[..], OPTIONS)?;
+    assert_eq!(chunks[0].digest(), SHA2_256::hash(&data[..LENGTHS[0]]));
+    Ok(())
+}
+
+#[test]
+fn cdc_read_sizes() -> Result<()> {
+    let data = data(256 * 1024, 7);
+    let expected = chunks(&data[..], OPTIONS)?;
+    assert_eq!(chunks(Trickle(&data), OPTIONS)?, expected);
+    Ok(())
+}
+
+#[test]
+fn cdc_insertion() -> Result<()> {
+    let original = data(512 * 1024, 3);
+    let mut modified = original.clone();
+    modified.splice(100_000..100_000, b"inserted data".iter().copied());
+
+    let original: Vec<Digest> = chunks(&original[..], OPTIONS)?.iter().map(Chunk::digest).collect();
+    let modified: Vec<Digest> = chunks(&modified[..], OPTIONS)?.iter().map(Chunk::digest).collect();
+    let shared = modified.iter().filter(|digest| original.contains(digest)).count();
+    assert!(
+        shared + 3 >= original.len(),
+        "{shared} of {} chunks shared",
+        original.len()
+    );
+    Ok(())
+}
+
+#[test]
+fn cdc_small_inputs() -> Result<()> {
+    assert!(chunks(&b""[..], OPTIONS)?.is_empty());
+
+    let chunks = chunks(&b"example data"[..], OPTIONS)?;
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].offset(), 0);
+    assert_eq!(chunks[0].length(), 12);
+    assert_eq!(
+        chunks[0].digest().to_hex_lowercase(),
+        "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+    );
+    Ok(())
+}
+
+#[test]
+fn cdc_uniform_data() -> Result<()> {
+    let data = vec![0; 100_000];
+    let lengths: Vec<usize> = chunks(&data[..], OPTIONS)?.iter().map(Chunk::length).collect();
+    assert_eq!(lengths, [32_768, 32_768, 32_768, 1_696]);
+    Ok(())
+}
+
+#[test]
+fn cdc_large_max_size() -> Result<()> {
+    // the buffer grows with the input rather than with the maximum chunk size
+    let options = ChunkerOptions::new(2 * 1024, 8 * 1024, usize::MAX / 4);
+    let data = vec![0; 100_000];
+    let lengths: Vec<usize> = chunks(&data[..], options)?.iter().map(Chunk::length).collect();
+    assert_eq!(lengths, [100_000]);
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-tokio"), ignore)]
+#[tokio::test]
+async fn async_runtime_tokio_cdc() -> Result<()> {
+    #[cfg(feature = "async-runtime-tokio")]
+    {
+        let data = data(256 * 1024, 5);
+        let expected = chunks(&data[..], OPTIONS)?;
+
+        let mut chunker = AsyncChunker::with_options(&data[..], OPTIONS);
+        let mut chunks = Vec::new();
+        while let Some(chunk) = chunker.next_chunk().await? {
+            chunks.push(chunk);
+        }
+        assert_eq!(chunks, expected);
+    }
+    Ok(())
+}
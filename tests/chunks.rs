@@ -0,0 +1,122 @@
+use std::fs::File;
+
+use assert_fs::prelude::{FileWriteBin, PathChild};
+use assert_fs::TempDir;
+#[cfg(feature = "async-runtime-tokio")]
+use chksum_sha2_256::chunks::{async_chksum_chunks, async_verify_chunks};
+use chksum_sha2_256::chunks::{chksum_chunks, verify_chunks, ChunkDigests};
+use chksum_sha2_256::SHA2_256;
+
+type BoxError = Box<dyn std::error::Error>;
+
+#[test]
+fn chunks_digests() -> Result<(), BoxError> {
+    let digests = chksum_chunks(b"example data", 5)?;
+    assert_eq!(digests.chunk_size(), 5);
+    assert_eq!(digests.length(), 12);
+    assert_eq!(
+        digests.chunks(),
+        [
+            SHA2_256::hash(b"examp"),
+            SHA2_256::hash(b"le da"),
+            SHA2_256::hash(b"ta"),
+        ]
+    );
+    assert_eq!(
+        digests.digest().to_hex_lowercase(),
+        "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+    );
+
+    let digests = chksum_chunks(b"example data", 4)?;
+    assert_eq!(digests.chunks().len(), 3);
+    assert_eq!(digests.chunks()[2], SHA2_256::hash(b"data"));
+
+    let digests = chksum_chunks(b"", 4)?;
+    assert!(digests.chunks().is_empty());
+    assert_eq!(digests.length(), 0);
+    assert_eq!(digests.digest(), SHA2_256::hash(b""));
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be greater than zero")]
+fn chunks_zero_size() {
+    let _ = chksum_chunks(b"data", 0);
+}
+
+#[test]
+fn chunks_file() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let data: Vec<u8> = (0..100_000u32).map(|index| (index % 251) as u8).collect();
+    temp_dir.child("file.bin").write_binary(&data)?;
+
+    let digests = chksum_chunks(File::open(temp_dir.child("file.bin").path())?, 16 * 1024)?;
+    assert_eq!(digests.length(), 100_000);
+    assert_eq!(digests.chunks().len(), 7);
+    for (chunk, digest) in data.chunks(16 * 1024).zip(digests.chunks()) {
+        assert_eq!(&SHA2_256::hash(chunk), digest);
+    }
+    assert_eq!(digests.digest(), SHA2_256::hash(&data));
+    Ok(())
+}
+
+#[test]
+fn chunks_verify() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let data = vec![0x5A; 10_000];
+    let expected = chksum_chunks(&data, 1_000)?;
+
+    temp_dir.child("intact.bin").write_binary(&data)?;
+    let corrupt = verify_chunks(File::open(temp_dir.child("intact.bin").path())?, &expected)?;
+    assert!(corrupt.is_empty());
+
+    let mut copy = data.clone();
+    copy[1_500] = 0;
+    copy[7_999] = 0;
+    copy[8_000] = 0;
+    temp_dir.child("corrupt.bin").write_binary(&copy)?;
+    let corrupt = verify_chunks(File::open(temp_dir.child("corrupt.bin").path())?, &expected)?;
+    assert_eq!(corrupt, [1, 7, 8]);
+
+    // a truncated copy misses the last chunks
+    let corrupt = verify_chunks(&data[..7_500], &expected)?;
+    assert_eq!(corrupt, [7, 8, 9]);
+
+    // excess data is reported as corrupt chunks
+    let mut longer = data.clone();
+    longer.extend_from_slice(&[0x5A; 1_001]);
+    let corrupt = verify_chunks(&longer, &expected)?;
+    assert_eq!(corrupt, [10, 11]);
+    Ok(())
+}
+
+#[test]
+fn chunks_new() -> Result<(), BoxError> {
+    let computed = chksum_chunks(b"example data", 4)?;
+    let received = ChunkDigests::new(4, 12, computed.chunks().to_vec(), computed.digest());
+    assert_eq!(received, computed);
+    assert_eq!(computed.corrupt_chunks(&received), Vec::<usize>::new());
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-tokio"), ignore)]
+#[tokio::test]
+async fn async_runtime_tokio_chunks() -> Result<(), BoxError> {
+    #[cfg(feature = "async-runtime-tokio")]
+    {
+        let temp_dir = TempDir::new()?;
+        let data = vec![0x5A; 10_000];
+        temp_dir.child("file.bin").write_binary(&data)?;
+
+        let file = tokio::fs::File::open(temp_dir.child("file.bin").path()).await?;
+        let digests = async_chksum_chunks(file, 1_000).await?;
+        assert_eq!(digests, chksum_chunks(&data, 1_000)?);
+
+        let mut copy = data.clone();
+        copy[4_321] = 0;
+        temp_dir.child("copy.bin").write_binary(&copy)?;
+        let file = tokio::fs::File::open(temp_dir.child("copy.bin").path()).await?;
+        assert_eq!(async_verify_chunks(file, &digests).await?, [4]);
+    }
+    Ok(())
+}
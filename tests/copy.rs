@@ -0,0 +1,128 @@
+#[cfg(feature = "writer")]
+use std::fs;
+use std::io::{self, ErrorKind, Write};
+
+#[cfg(feature = "writer")]
+use assert_fs::TempDir;
+#[cfg(feature = "writer")]
+use chksum_sha2_256::writer::AtomicWriter;
+#[cfg(feature = "async-runtime-tokio")]
+use chksum_sha2_256::{async_copy, async_copy_verified};
+use chksum_sha2_256::{copy, copy_verified, Digest, Error, Result, SHA2_256};
+
+const DIGEST: &str = "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061";
+
+/// A writer recording whether it was flushed.
+#[derive(Default)]
+struct Destination {
+    data: Vec<u8>,
+    flushed: bool,
+}
+
+impl Write for Destination {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushed = true;
+        Ok(())
+    }
+}
+
+fn is_mismatch(error: &Error) -> bool {
+    matches!(error, Error::Io(error) if error.kind() == ErrorKind::InvalidData)
+}
+
+#[test]
+fn copy_data() -> Result<()> {
+    let mut destination = Destination::default();
+    let (length, digest) = copy(&b"example data"[..], &mut destination)?;
+    assert_eq!(length, 12);
+    assert_eq!(digest.to_hex_lowercase(), DIGEST);
+    assert_eq!(destination.data, b"example data");
+    assert!(destination.flushed);
+
+    let (length, digest) = copy(&b""[..], io::sink())?;
+    assert_eq!(length, 0);
+    assert_eq!(digest, SHA2_256::hash(b""));
+
+    let data = vec![0x5A; 100_000];
+    let mut destination = Vec::new();
+    let (length, digest) = copy(&data[..], &mut destination)?;
+    assert_eq!(length, 100_000);
+    assert_eq!(digest, SHA2_256::hash(&data));
+    assert_eq!(destination, data);
+
+    Ok(())
+}
+
+#[test]
+fn copy_data_verified() -> Result<()> {
+    let expected = Digest::try_from(DIGEST).unwrap();
+
+    let mut destination = Destination::default();
+    let length = copy_verified(&b"example data"[..], &mut destination, expected)?;
+    assert_eq!(length, 12);
+    assert!(destination.flushed);
+
+    let mut destination = Destination::default();
+    let error = copy_verified(&b"tampered data"[..], &mut destination, expected).unwrap_err();
+    assert!(is_mismatch(&error));
+    // the unverified data was written, only the flush is skipped
+    assert_eq!(destination.data, b"tampered data");
+    assert!(!destination.flushed);
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "writer"), ignore)]
+#[test]
+fn copy_data_verified_atomically() -> Result<()> {
+    #[cfg(feature = "writer")]
+    {
+        let expected = Digest::try_from(DIGEST).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("destination");
+
+        // a mismatching copy leaves only the temporary file, which is removed on abort
+        let mut writer = AtomicWriter::create(&path)?;
+        let error = copy_verified(&b"tampered data"[..], &mut writer, expected).unwrap_err();
+        assert!(is_mismatch(&error));
+        writer.abort()?;
+        assert_eq!(fs::read_dir(temp_dir.path())?.count(), 0);
+
+        let mut writer = AtomicWriter::create(&path)?;
+        copy_verified(&b"example data"[..], &mut writer, expected)?;
+        writer.commit()?;
+        assert_eq!(fs::read(&path)?, b"example data");
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-tokio"), ignore)]
+#[tokio::test]
+async fn async_runtime_tokio_copy_data() -> Result<()> {
+    #[cfg(feature = "async-runtime-tokio")]
+    {
+        let mut destination = Vec::new();
+        let (length, digest) = async_copy(&b"example data"[..], &mut destination).await?;
+        assert_eq!(length, 12);
+        assert_eq!(digest.to_hex_lowercase(), DIGEST);
+        assert_eq!(destination, b"example data");
+
+        let expected = Digest::try_from(DIGEST).unwrap();
+        let length = async_copy_verified(&b"example data"[..], tokio::io::sink(), expected).await?;
+        assert_eq!(length, 12);
+
+        let mut destination = Vec::new();
+        let error = async_copy_verified(&b"tampered data"[..], &mut destination, expected)
+            .await
+            .unwrap_err();
+        assert!(is_mismatch(&error));
+        assert_eq!(destination, b"tampered data");
+    }
+
+    Ok(())
+}
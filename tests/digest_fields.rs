@@ -0,0 +1,120 @@
+use std::io::ErrorKind;
+#[cfg(feature = "writer")]
+use std::io::Write;
+
+use chksum_sha2_256::digest_fields::{self, ParseError};
+use chksum_sha2_256::{Error, Result, SHA2_256};
+
+const BODY: &[u8] = b"{\"hello\": \"world\"}";
+const VALUE: &str = "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:";
+
+fn error_kind(result: Result<()>) -> Option<ErrorKind> {
+    match result {
+        Err(Error::Io(error)) => Some(error.kind()),
+        _ => None,
+    }
+}
+
+#[test]
+fn format() -> Result<()> {
+    assert_eq!(digest_fields::format(SHA2_256::hash(BODY)), VALUE);
+    assert_eq!(digest_fields::chksum(BODY)?, VALUE);
+    assert_eq!(
+        digest_fields::chksum(b"")?,
+        "sha-256=:47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=:"
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "writer"), ignore)]
+#[test]
+fn format_writer() -> Result<()> {
+    #[cfg(feature = "writer")]
+    {
+        let mut writer = chksum_sha2_256::writer::new(Vec::new());
+        writer.write_all(&BODY[..8])?;
+        writer.write_all(&BODY[8..])?;
+        assert_eq!(digest_fields::format_writer(&writer), VALUE);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse() {
+    let digest = Some(SHA2_256::hash(BODY));
+    assert_eq!(digest_fields::parse(VALUE), Ok(digest));
+    assert_eq!(
+        digest_fields::parse(&format!("sha-512=:YQ==:, {VALUE};foo=bar, unixsum=\"a,b\"")),
+        Ok(digest)
+    );
+    assert_eq!(
+        digest_fields::parse(&format!("  {VALUE}\t,id-sha-256=:YQ==:  ")),
+        Ok(digest)
+    );
+    assert_eq!(digest_fields::parse("md5=:jXd/OF09/siBXSD3SWAm3A==:"), Ok(None));
+    assert_eq!(digest_fields::parse(""), Ok(None));
+
+    assert_eq!(digest_fields::parse("sha-256=:YQ==:"), Err(ParseError::InvalidDigest));
+    assert_eq!(digest_fields::parse("sha-256=abc"), Err(ParseError::InvalidDigest));
+    assert_eq!(digest_fields::parse("sha-256=:!!:"), Err(ParseError::InvalidDigest));
+    assert_eq!(
+        digest_fields::parse(&format!("{VALUE},")),
+        Err(ParseError::InvalidDictionary)
+    );
+    assert_eq!(
+        digest_fields::parse("SHA-256=:YQ==:"),
+        Err(ParseError::InvalidDictionary)
+    );
+    assert_eq!(
+        digest_fields::parse("sha-256=:YQ=="),
+        Err(ParseError::InvalidDictionary)
+    );
+}
+
+#[test]
+fn verify() -> Result<()> {
+    digest_fields::verify(VALUE, BODY)?;
+    digest_fields::verify(&format!("md5=:jXd/OF09/siBXSD3SWAm3A==:, {VALUE}"), BODY)?;
+
+    assert_eq!(
+        error_kind(digest_fields::verify(VALUE, b"tampered")),
+        Some(ErrorKind::InvalidData)
+    );
+    assert_eq!(
+        error_kind(digest_fields::verify("md5=:YQ==:", BODY)),
+        Some(ErrorKind::Unsupported)
+    );
+    assert_eq!(
+        error_kind(digest_fields::verify("sha-256", BODY)),
+        Some(ErrorKind::InvalidData)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn legacy() -> Result<()> {
+    let value = "SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=";
+    assert_eq!(digest_fields::format_legacy(SHA2_256::hash(BODY)), value);
+    assert_eq!(
+        digest_fields::parse_legacy(
+            "MD5=jXd/OF09/siBXSD3SWAm3A==, sha-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE="
+        ),
+        Ok(Some(SHA2_256::hash(BODY)))
+    );
+    assert_eq!(digest_fields::parse_legacy("MD5=jXd/OF09/siBXSD3SWAm3A=="), Ok(None));
+    assert_eq!(
+        digest_fields::parse_legacy("SHA-256"),
+        Err(ParseError::InvalidDictionary)
+    );
+
+    digest_fields::verify_legacy(value, BODY)?;
+    assert_eq!(
+        error_kind(digest_fields::verify_legacy(value, b"tampered")),
+        Some(ErrorKind::InvalidData)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+#[cfg(feature = "async-runtime-futures")]
+use chksum_sha2_256::futures::{
+    async_chksum,
+    async_chksum_stream,
+    async_chksum_with,
+    async_try_chksum_stream,
+    AsyncReader,
+    AsyncWriter,
+    DigestStream,
+    TryDigestStream,
+};
+#[cfg(feature = "async-runtime-futures")]
+use chksum_sha2_256::ChksumOptions;
+use chksum_sha2_256::Result;
+#[cfg(feature = "async-runtime-futures")]
+use futures::executor::block_on;
+#[cfg(feature = "async-runtime-futures")]
+use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+#[cfg(feature = "async-runtime-futures")]
+use futures::{stream, Stream, StreamExt};
+
+#[cfg(feature = "async-runtime-futures")]
+const DIGEST: &str = "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061";
+
+#[cfg_attr(not(feature = "async-runtime-futures"), ignore)]
+#[test]
+fn futures_chksum() -> Result<()> {
+    #[cfg(feature = "async-runtime-futures")]
+    block_on(async {
+        let digest = async_chksum(&b"example data"[..]).await?;
+        assert_eq!(digest.to_hex_lowercase(), DIGEST);
+
+        let mut options = ChksumOptions::with_buffer_size(3);
+        let digest = async_chksum_with(Cursor::new(b"example data"), &mut options).await?;
+        assert_eq!(digest.to_hex_lowercase(), DIGEST);
+
+        let digest = async_chksum(&b""[..]).await?;
+        assert_eq!(
+            digest.to_hex_lowercase(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        Ok::<_, chksum_sha2_256::Error>(())
+    })?;
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-futures"), ignore)]
+#[test]
+fn futures_reader() -> Result<()> {
+    #[cfg(feature = "async-runtime-futures")]
+    block_on(async {
+        let mut reader = AsyncReader::new(Cursor::new(b"example data"));
+        let mut buffer = [0u8; 5];
+        reader.read_exact(&mut buffer).await?;
+        assert_eq!(&buffer, b"examp");
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).await?;
+        assert_eq!(rest, b"le data");
+        assert_eq!(reader.digest().to_hex_lowercase(), DIGEST);
+        assert_eq!(reader.into_inner().position(), 12);
+
+        Ok::<_, std::io::Error>(())
+    })?;
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-futures"), ignore)]
+#[test]
+fn futures_writer() -> Result<()> {
+    #[cfg(feature = "async-runtime-futures")]
+    block_on(async {
+        let mut writer = AsyncWriter::new(Vec::new());
+        writer.write_all(b"example ").await?;
+        writer.write_all(b"data").await?;
+        writer.close().await?;
+        assert_eq!(writer.digest().to_hex_lowercase(), DIGEST);
+        assert_eq!(writer.into_inner(), b"example data");
+
+        Ok::<_, std::io::Error>(())
+    })?;
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-futures"), ignore)]
+#[test]
+fn futures_stream() {
+    #[cfg(feature = "async-runtime-futures")]
+    block_on(async {
+        let chunks = vec![b"exa".to_vec(), Vec::new(), b"mple data".to_vec()];
+        let digest = async_chksum_stream(stream::iter(chunks.clone())).await;
+        assert_eq!(digest.to_hex_lowercase(), DIGEST);
+
+        let mut stream = DigestStream::new(stream::iter(chunks.clone()));
+        assert_eq!(stream.size_hint(), (3, Some(3)));
+        assert_eq!(stream.next().await.as_deref(), Some(&b"exa"[..]));
+        assert_eq!(stream.digest(), None);
+        assert_eq!(stream.by_ref().collect::<Vec<_>>().await, chunks[1..]);
+        assert_eq!(stream.digest().unwrap().to_hex_lowercase(), DIGEST);
+
+        let digest = async_chksum_stream(stream::empty::<&[u8]>()).await;
+        assert_eq!(
+            digest.to_hex_lowercase(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    });
+}
+
+#[cfg_attr(not(feature = "async-runtime-futures"), ignore)]
+#[test]
+fn futures_try_stream() {
+    #[cfg(feature = "async-runtime-futures")]
+    block_on(async {
+        let chunks = [Ok::<_, &str>(&b"example "[..]), Ok(b"data")];
+        let digest = async_try_chksum_stream(stream::iter(chunks)).await;
+        assert_eq!(digest.unwrap().to_hex_lowercase(), DIGEST);
+
+        let chunks = [Ok(&b"example "[..]), Err("broken"), Ok(b"data")];
+        assert_eq!(async_try_chksum_stream(stream::iter(chunks)).await, Err("broken"));
+
+        let mut stream = TryDigestStream::new(stream::iter([Ok::<_, &str>(&b"example "[..]), Ok(b"data")]));
+        while stream.next().await.is_some() {}
+        assert_eq!(stream.digest().unwrap().to_hex_lowercase(), DIGEST);
+
+        let mut stream = TryDigestStream::new(stream::iter(chunks));
+        assert_eq!(stream.by_ref().collect::<Vec<_>>().await, chunks);
+        assert_eq!(stream.digest(), None);
+    });
+}
@@ -0,0 +1,113 @@
+use std::io::Error as IoError;
+
+use assert_fs::fixture::FixtureError;
+#[cfg(unix)]
+use assert_fs::prelude::FileWriteStr;
+use assert_fs::prelude::PathChild;
+use assert_fs::TempDir;
+use chksum_sha2_256::git::{self, Mode, ObjectType, TreeEntry};
+use chksum_sha2_256::{Digest, Error as ChksumError};
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Chksum(#[from] ChksumError),
+    #[error(transparent)]
+    Fixture(#[from] FixtureError),
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+#[test]
+fn blob_id() -> Result<(), Error> {
+    assert_eq!(
+        git::blob_id(b"").to_hex_lowercase(),
+        "473a0f4c3be8a93681a267e3b1e9a7dcda1185436fe141f7749120a303721813"
+    );
+    assert_eq!(
+        git::blob_id(b"hello\n").to_hex_lowercase(),
+        "2cf8d83d9ee29543b34a87727421fdecb7e3f3a183d337639025de576db9ebb4"
+    );
+
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.child("hello.txt");
+    std::fs::write(file.path(), b"hello\n")?;
+    assert_eq!(git::blob_id_file(file.path())?, git::blob_id(b"hello\n"));
+
+    Ok(())
+}
+
+#[test]
+fn commit_id() {
+    let content = "tree 0d2f346d8a28ba173a39e46ff2b821682eb509e4822abc2d1b03762e303a703c\nauthor a <a@b> 1792358631 \
+                   +0000\ncommitter a <a@b> 1792358631 +0000\n\ninit\n";
+    assert_eq!(
+        git::object_id(ObjectType::Commit, content).to_hex_lowercase(),
+        "512129311dd528f5e1208c2fc99d0299be322e06fe8214d6f7e295ada2cd4d95"
+    );
+}
+
+#[test]
+fn tree_id_sorting() {
+    // "sub/" sorts after "sub.txt", so the directory goes last
+    let entries = [
+        TreeEntry::new(
+            Mode::Directory,
+            "sub",
+            Digest::try_from("33be97e0998c73541d5005d56c2408f8cd8eccca27997d6055abbe01a15c195c").unwrap(),
+        ),
+        TreeEntry::new(Mode::File, "sub.txt", git::blob_id(b"y")),
+        TreeEntry::new(Mode::Executable, "run.sh", git::blob_id(b"#!/bin/sh\n")),
+        TreeEntry::new(Mode::Symlink, "link", git::blob_id(b"a.txt")),
+        TreeEntry::new(Mode::File, "a.txt", git::blob_id(b"hello\n")),
+    ];
+    assert_eq!(
+        git::tree_id(&entries).to_hex_lowercase(),
+        "0d2f346d8a28ba173a39e46ff2b821682eb509e4822abc2d1b03762e303a703c"
+    );
+}
+
+#[test]
+fn tree_id_empty() -> Result<(), Error> {
+    let temp_dir = TempDir::new()?;
+    std::fs::create_dir(temp_dir.child(".git").path())?;
+    assert_eq!(git::tree_id_dir(temp_dir.path())?, git::tree_id(&[]));
+
+    Ok(())
+}
+
+#[cfg_attr(not(unix), ignore)]
+#[test]
+fn tree_id_dir() -> Result<(), Error> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let temp_dir = TempDir::new()?;
+        temp_dir.child("a.txt").write_str("hello\n")?;
+        temp_dir.child("run.sh").write_str("#!/bin/sh\n")?;
+        std::fs::set_permissions(temp_dir.child("run.sh").path(), std::fs::Permissions::from_mode(0o755))?;
+        symlink("a.txt", temp_dir.child("link").path())?;
+        temp_dir.child("sub.txt").write_str("y")?;
+        temp_dir.child("sub/deep/f").write_str("x")?;
+        std::fs::create_dir_all(temp_dir.child("empty/nested").path())?;
+        temp_dir.child(".git/HEAD").write_str("ref: refs/heads/main\n")?;
+
+        assert_eq!(
+            git::tree_id_dir(temp_dir.child("sub").path())?.to_hex_lowercase(),
+            "33be97e0998c73541d5005d56c2408f8cd8eccca27997d6055abbe01a15c195c"
+        );
+        assert_eq!(
+            git::tree_id_dir(temp_dir.path())?.to_hex_lowercase(),
+            "0d2f346d8a28ba173a39e46ff2b821682eb509e4822abc2d1b03762e303a703c"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "entry name must not contain slash or NUL byte")]
+fn tree_entry_invalid_name() {
+    let _ = TreeEntry::new(Mode::File, "a/b", git::blob_id(b""));
+}
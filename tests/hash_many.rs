@@ -0,0 +1,49 @@
+use chksum_sha2_256::{hash_many, SHA2_256};
+
+#[test]
+fn empty() {
+    let data: [&[u8]; 0] = [];
+    let digests = hash_many(&data);
+    assert!(digests.is_empty());
+}
+
+#[test]
+fn single() {
+    let digests = SHA2_256::hash_many(&["example data"]);
+    assert_eq!(digests.len(), 1);
+    assert_eq!(
+        digests[0].to_hex_lowercase(),
+        "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+    );
+}
+
+#[test]
+fn matches_hash() {
+    let data: Vec<u8> = (0..=u8::MAX).cycle().take(4096 + 200).collect();
+    // lengths around padding and block boundaries, in shuffled order
+    let messages: Vec<&[u8]> = [
+        0, 4096, 55, 56, 1, 63, 64, 65, 119, 120, 127, 128, 4096, 200, 3, 1000, 4000, 0, 4150, 17, 4096,
+    ]
+    .iter()
+    .map(|&length| &data[..length])
+    .collect();
+
+    let digests = SHA2_256::hash_many(&messages);
+    assert_eq!(digests.len(), messages.len());
+    for (message, digest) in messages.iter().zip(&digests) {
+        assert_eq!(
+            digest.to_hex_lowercase(),
+            SHA2_256::hash(message).to_hex_lowercase(),
+            "length {}",
+            message.len()
+        );
+    }
+}
+
+#[test]
+fn vectors() {
+    let messages = vec![vec![0u8; 4096]; 20];
+    let digests = hash_many(&messages);
+    let expected = SHA2_256::hash(&messages[0]).to_hex_lowercase();
+    assert!(digests.iter().all(|digest| digest.to_hex_lowercase() == expected));
+}
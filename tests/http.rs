@@ -0,0 +1,186 @@
+#[cfg(feature = "http")]
+mod common {
+    use std::collections::VecDeque;
+    use std::convert::Infallible;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use bytes::Bytes;
+    use http::HeaderMap;
+    use http_body::{Body, Frame};
+
+    /// A body yielding the given frames.
+    pub struct Frames(pub VecDeque<Frame<Bytes>>);
+
+    impl Frames {
+        pub fn new(data: &[&'static [u8]], trailers: Option<HeaderMap>) -> Self {
+            let mut frames: VecDeque<_> = data.iter().map(|data| Frame::data(Bytes::from_static(data))).collect();
+            frames.extend(trailers.map(Frame::trailers));
+            Self(frames)
+        }
+    }
+
+    impl Body for Frames {
+        type Data = Bytes;
+        type Error = Infallible;
+
+        fn poll_frame(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+            Poll::Ready(self.get_mut().0.pop_front().map(Ok))
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+use std::convert::Infallible;
+
+#[cfg(feature = "http")]
+use chksum_sha2_256::http::{ContentDigestLayer, DigestBody, ResponseMode, VerifyError};
+#[cfg(feature = "http")]
+use chksum_sha2_256::SHA2_256;
+#[cfg(feature = "http")]
+use common::Frames;
+#[cfg(feature = "http")]
+use http::{HeaderMap, HeaderValue, Request, Response, StatusCode};
+#[cfg(feature = "http")]
+use http_body_util::{BodyExt, Empty, Full};
+#[cfg(feature = "http")]
+use tower::Layer;
+#[cfg(feature = "http")]
+use tower::{service_fn, ServiceBuilder, ServiceExt};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[cfg(feature = "http")]
+const DIGEST: &str = "sha-256=:Om6weQ85rIfJTzhWst0sXREOaBFgImGpqSPTuyOtyLc=:";
+
+#[cfg_attr(not(feature = "http"), ignore)]
+#[tokio::test]
+async fn response_trailer() -> Result<(), BoxError> {
+    #[cfg(feature = "http")]
+    {
+        let service = ServiceBuilder::new()
+            .layer(ContentDigestLayer::new())
+            .service(service_fn(|_| {
+                async { Ok::<_, Infallible>(Response::new(Frames::new(&[b"da", b"", b"ta"], None))) }
+            }));
+
+        let response = service.oneshot(Request::new(Empty::<&[u8]>::new())).await?;
+        assert_eq!(response.headers()["trailer"], "content-digest");
+        assert!(response.headers().get("content-digest").is_none());
+
+        let collected = response.into_body().collect().await?;
+        assert_eq!(collected.trailers().unwrap()["content-digest"], DIGEST);
+        assert_eq!(collected.to_bytes(), &b"data"[..]);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "http"), ignore)]
+#[tokio::test]
+async fn response_trailer_merged() -> Result<(), BoxError> {
+    #[cfg(feature = "http")]
+    {
+        let service = ContentDigestLayer::new().layer(service_fn(|_| {
+            async {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("x-checksum", HeaderValue::from_static("1"));
+                Ok::<_, Infallible>(Response::new(Frames::new(&[b"data"], Some(trailers))))
+            }
+        }));
+
+        let response = service.oneshot(Request::new(Empty::<&[u8]>::new())).await?;
+        let collected = response.into_body().collect().await?;
+        let trailers = collected.trailers().unwrap();
+        assert_eq!(trailers["content-digest"], DIGEST);
+        assert_eq!(trailers["x-checksum"], "1");
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "http"), ignore)]
+#[tokio::test]
+async fn response_buffer() -> Result<(), BoxError> {
+    #[cfg(feature = "http")]
+    {
+        let service = ServiceBuilder::new()
+            .layer(ContentDigestLayer::new().with_response_mode(ResponseMode::Buffer))
+            .service(service_fn(|_| {
+                async { Ok::<_, Infallible>(Response::new(Frames::new(&[b"da", b"ta"], None))) }
+            }));
+
+        let response = service.oneshot(Request::new(Empty::<&[u8]>::new())).await?;
+        assert_eq!(response.headers()["content-digest"], DIGEST);
+        assert_eq!(response.headers()["content-length"], "4");
+        assert!(response.headers().get("trailer").is_none());
+
+        let collected = response.into_body().collect().await?;
+        assert!(collected.trailers().is_none());
+        assert_eq!(collected.to_bytes(), &b"data"[..]);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "http"), ignore)]
+#[tokio::test]
+async fn request_verification() -> Result<(), BoxError> {
+    #[cfg(feature = "http")]
+    {
+        let layer = ContentDigestLayer::new();
+        let verify = |layer: ContentDigestLayer, digest, body| {
+            async move {
+                let service = layer.layer(service_fn(|request: Request<DigestBody<Full<&'static [u8]>>>| {
+                    async {
+                        let body = request.into_body().collect().await?.to_bytes();
+                        Ok::<_, BoxError>(Response::new(Full::new(body)))
+                    }
+                }));
+                let mut request = Request::new(Full::new(body));
+                if let Some(digest) = digest {
+                    request
+                        .headers_mut()
+                        .insert("content-digest", HeaderValue::from_static(digest));
+                }
+                let response = service.oneshot(request).await?;
+                Ok::<_, BoxError>(response.into_body().collect().await?.to_bytes())
+            }
+        };
+
+        assert_eq!(verify(layer, Some(DIGEST), &b"data"[..]).await?, &b"data"[..]);
+        assert_eq!(verify(layer, None, &b"tampered"[..]).await?, &b"tampered"[..]);
+        assert_eq!(
+            verify(layer, Some("md5=:jXd/OF09/siBXSD3SWAm3A==:"), &b"tampered"[..]).await?,
+            &b"tampered"[..]
+        );
+
+        let error = verify(layer, Some(DIGEST), &b"tampered"[..]).await.unwrap_err();
+        let error = error.downcast_ref::<VerifyError>().unwrap();
+        assert_eq!(
+            *error,
+            VerifyError::Mismatch {
+                expected: SHA2_256::hash(b"data"),
+                actual: SHA2_256::hash(b"tampered"),
+            }
+        );
+
+        // malformed fields are rejected without calling the inner service
+        let service = layer.layer(service_fn(|_request: Request<DigestBody<Full<&'static [u8]>>>| {
+            async { Err::<Response<Empty<&'static [u8]>>, _>("inner service called") }
+        }));
+        let mut request = Request::new(Full::new(&b"data"[..]));
+        request
+            .headers_mut()
+            .insert("content-digest", HeaderValue::from_static("sha-256=:YQ==:"));
+        let response = service.oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = response.into_body().collect().await?.to_bytes();
+        assert!(body.starts_with(b"invalid `Content-Digest` field"));
+
+        let layer = layer.with_request_verification(false);
+        assert_eq!(verify(layer, Some(DIGEST), &b"tampered"[..]).await?, &b"tampered"[..]);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use std::fs;
+
+use assert_fs::prelude::{FileWriteBin, PathChild, PathCreateDir};
+use assert_fs::TempDir;
+use chksum_sha2_256::manifest::{Change, EntryKind, Manifest, ParseError};
+use chksum_sha2_256::SHA2_256;
+
+type BoxError = Box<dyn std::error::Error>;
+
+fn fixture() -> Result<TempDir, BoxError> {
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("file").write_binary(b"data")?;
+    temp_dir.child("dir").create_dir_all()?;
+    temp_dir.child("dir").child("nested").write_binary(b"example data")?;
+    temp_dir.child("gone").write_binary(b"data")?;
+    Ok(temp_dir)
+}
+
+#[test]
+fn manifest_snapshot() -> Result<(), BoxError> {
+    let temp_dir = fixture()?;
+    let manifest = Manifest::snapshot(&temp_dir)?;
+
+    let paths: Vec<_> = manifest.entries().iter().map(|entry| entry.path()).collect();
+    assert_eq!(paths, ["dir", "dir/nested", "file", "gone"]);
+
+    let dir = manifest.get("dir").unwrap();
+    assert_eq!(dir.kind(), EntryKind::Directory);
+    assert_eq!(dir.size(), None);
+    assert_eq!(dir.digest(), None);
+
+    let nested = manifest.get("dir/nested").unwrap();
+    assert_eq!(nested.kind(), EntryKind::File);
+    assert_eq!(nested.size(), Some(12));
+    assert_eq!(nested.digest(), Some(SHA2_256::hash(b"example data")));
+    assert!(manifest.get("missing").is_none());
+
+    assert!(Manifest::snapshot(temp_dir.child("missing")).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn manifest_text_format() -> Result<(), BoxError> {
+    let temp_dir = fixture()?;
+    let manifest = Manifest::snapshot(&temp_dir)?;
+
+    let text = manifest.to_string();
+    let lines: Vec<_> = text.lines().collect();
+    assert_eq!(lines[0], "chksum-sha2-256 manifest v1");
+    assert!(lines[1].starts_with("dir "));
+    assert!(lines[1].ends_with(" - - dir"));
+    assert!(lines[2].ends_with(" 12 44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061 dir/nested"));
+    assert_eq!(text.parse::<Manifest>()?, manifest);
+
+    let text = "chksum-sha2-256 manifest v1\nfile 0644 4 \
+                3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7 with space\\nand line feed\n";
+    let manifest: Manifest = text.parse()?;
+    assert_eq!(manifest.entries()[0].path(), "with space\nand line feed");
+    assert_eq!(manifest.entries()[0].mode(), 0o644);
+    assert_eq!(manifest.to_string(), text);
+
+    assert_eq!("".parse::<Manifest>(), Err(ParseError::MissingHeader));
+    assert_eq!(
+        "chksum-sha2-256 manifest v1\ndir 0755 4 - dir\n".parse::<Manifest>(),
+        Err(ParseError::InvalidEntry(2))
+    );
+    assert_eq!(
+        "chksum-sha2-256 manifest v1\ndir 0755 - - dir\ndir 0755 - - dir\n".parse::<Manifest>(),
+        Err(ParseError::DuplicatePath(3))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn manifest_json_format() -> Result<(), BoxError> {
+    let text = "chksum-sha2-256 manifest v1\ndir 0755 - - dir\nfile 0644 4 \
+                3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7 dir/\"quoted\"\n";
+    let manifest: Manifest = text.parse()?;
+    assert_eq!(
+        manifest.to_json(),
+        r#"{
+  "version": 1,
+  "entries": [
+    {"path": "dir", "type": "dir", "mode": "0755", "size": null, "digest": null},
+    {"path": "dir/\"quoted\"", "type": "file", "mode": "0644", "size": 4, "digest": "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7"}
+  ]
+}
+"#
+    );
+    assert_eq!(
+        Manifest::default().to_json(),
+        "{\n  \"version\": 1,\n  \"entries\": []\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn manifest_json_round_trip() -> Result<(), BoxError> {
+    let temp_dir = fixture()?;
+    // such names are invalid on Windows
+    #[cfg(unix)]
+    temp_dir
+        .child("with \"quotes\"\nand\tcontrols\u{1}")
+        .write_binary(b"")?;
+    let manifest = Manifest::snapshot(&temp_dir)?;
+    assert_eq!(Manifest::from_json(&manifest.to_json())?, manifest);
+    assert_eq!(
+        Manifest::from_json(&Manifest::default().to_json())?,
+        Manifest::default()
+    );
+
+    // any layout and escapes are accepted, unknown members are ignored
+    let json = r#"{"entries": [{"digest": null, "size": null, "mode": "0755", "type": "dir", "path": "d\u00efr"},
+        {"path": "\ud83d\ude00", "type": "symlink", "mode": "0777", "size": 3, "extra": [true, {}],
+         "digest": "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7"}], "version": 1}"#;
+    let manifest = Manifest::from_json(json)?;
+    let paths: Vec<_> = manifest.entries().iter().map(|entry| entry.path()).collect();
+    assert_eq!(paths, ["d\u{ef}r", "\u{1f600}"]);
+
+    assert_eq!(Manifest::from_json(""), Err(ParseError::InvalidJson(1)));
+    assert_eq!(Manifest::from_json("[]"), Err(ParseError::InvalidJson(1)));
+    assert_eq!(
+        Manifest::from_json("{\n  \"version\": 1,\n  \"entries\": [\n"),
+        Err(ParseError::InvalidJson(4))
+    );
+    assert_eq!(
+        Manifest::from_json(r#"{"version": 2, "entries": []}"#),
+        Err(ParseError::InvalidJson(1))
+    );
+    assert_eq!(
+        Manifest::from_json(
+            "{\"version\": 1, \"entries\": [\n{\"path\": \"dir\", \"type\": \"dir\", \"mode\": \"0755\", \"size\": 1, \
+             \"digest\": null}]}"
+        ),
+        Err(ParseError::InvalidEntry(2))
+    );
+    let entry = r#"{"path": "dir", "type": "dir", "mode": "0755", "size": null, "digest": null}"#;
+    assert_eq!(
+        Manifest::from_json(&format!("{{\"version\": 1, \"entries\": [\n{entry},\n{entry}]}}")),
+        Err(ParseError::DuplicatePath(3))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn manifest_diff() -> Result<(), BoxError> {
+    let temp_dir = fixture()?;
+    let baseline = Manifest::snapshot(&temp_dir)?;
+    assert!(baseline.diff_dir(&temp_dir)?.is_empty());
+
+    temp_dir.child("added").write_binary(b"data")?;
+    fs::remove_file(temp_dir.child("gone"))?;
+    temp_dir.child("dir").child("nested").write_binary(b"tampered")?;
+    fs::remove_file(temp_dir.child("file"))?;
+    temp_dir.child("file").create_dir_all()?;
+
+    let changes = baseline.diff_dir(&temp_dir)?;
+    let summary: Vec<_> = changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        summary,
+        [
+            "added: added",
+            "modified: dir/nested",
+            "type changed: file",
+            "removed: gone",
+        ]
+    );
+    match &changes[1] {
+        Change::Modified { old, new } => {
+            assert_eq!(old.digest(), Some(SHA2_256::hash(b"example data")));
+            assert_eq!(new.digest(), Some(SHA2_256::hash(b"tampered")));
+        },
+        change => panic!("unexpected change {change:?}"),
+    }
+
+    let current = Manifest::snapshot(&temp_dir)?;
+    assert_eq!(current.diff(&baseline).len(), 4);
+    assert!(matches!(current.diff(&baseline)[0], Change::Removed(_)));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn manifest_unix_metadata() -> Result<(), BoxError> {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let temp_dir = fixture()?;
+    symlink("file", temp_dir.child("link"))?;
+    let baseline = Manifest::snapshot(&temp_dir)?;
+
+    let link = baseline.get("link").unwrap();
+    assert_eq!(link.kind(), EntryKind::Symlink);
+    assert_eq!(link.size(), Some(4));
+    assert_eq!(link.digest(), Some(SHA2_256::hash(b"file")));
+
+    fs::set_permissions(temp_dir.child("file"), fs::Permissions::from_mode(0o755))?;
+    fs::remove_file(temp_dir.child("link"))?;
+    symlink("gone", temp_dir.child("link"))?;
+
+    let changes = baseline.diff_dir(&temp_dir)?;
+    let summary: Vec<_> = changes.iter().map(ToString::to_string).collect();
+    assert_eq!(summary, ["modified: file", "modified: link"]);
+    assert_eq!(Manifest::snapshot(&temp_dir)?.get("file").unwrap().mode(), 0o755);
+
+    Ok(())
+}
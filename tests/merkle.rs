@@ -0,0 +1,151 @@
+use chksum_sha2_256::merkle::{self, ConsistencyProof, InclusionProof, MerkleTree};
+use chksum_sha2_256::{Digest, Result};
+
+// test vectors from the Certificate Transparency reference implementation
+const LEAVES: [&[u8]; 8] = [
+    b"",
+    b"\x00",
+    b"\x10",
+    b"\x20\x21",
+    b"\x30\x31",
+    b"\x40\x41\x42\x43",
+    b"\x50\x51\x52\x53\x54\x55\x56\x57",
+    b"\x60\x61\x62\x63\x64\x65\x66\x67\x68\x69\x6a\x6b\x6c\x6d\x6e\x6f",
+];
+
+const ROOTS: [&str; 8] = [
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+
+#[test]
+fn empty_root() {
+    let tree = MerkleTree::new();
+    assert!(tree.is_empty());
+    assert_eq!(
+        tree.root().to_hex_lowercase(),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
+
+#[test]
+fn roots() {
+    for (size, root) in (1..=LEAVES.len()).zip(ROOTS) {
+        let tree = MerkleTree::from_leaves(&LEAVES[..size]);
+        assert_eq!(tree.len(), size);
+        assert_eq!(tree.root().to_hex_lowercase(), root, "size {size}");
+    }
+
+    let tree: MerkleTree = LEAVES.iter().collect();
+    for (size, root) in (1..=LEAVES.len()).zip(ROOTS) {
+        let digest = tree.root_at(size).map(|digest| digest.to_hex_lowercase());
+        assert_eq!(digest.as_deref(), Some(root));
+    }
+    assert_eq!(tree.root_at(LEAVES.len() + 1), None);
+}
+
+#[test]
+fn inclusion_proofs() {
+    let leaves: Vec<String> = (0..37).map(|index| format!("leaf {index}")).collect();
+    for size in 1..=leaves.len() {
+        let tree = MerkleTree::from_leaves(&leaves[..size]);
+        let root = tree.root();
+        for (index, leaf) in leaves[..size].iter().enumerate() {
+            let proof = tree.inclusion_proof(index).unwrap();
+            assert_eq!(proof.index(), index);
+            assert_eq!(proof.tree_size(), size);
+            assert!(proof.verify(leaf, &root), "size {size}, index {index}");
+            assert!(!proof.verify("other", &root));
+        }
+        assert_eq!(tree.inclusion_proof(size), None);
+    }
+}
+
+#[test]
+fn inclusion_proof_tampered() {
+    let tree = MerkleTree::from_leaves(LEAVES);
+    let root = tree.root();
+    let proof = tree.inclusion_proof(5).unwrap();
+
+    let wrong_index = InclusionProof::new(4, proof.tree_size(), proof.path().to_vec());
+    assert!(!wrong_index.verify(LEAVES[5], &root));
+
+    let wrong_size = InclusionProof::new(5, proof.tree_size() + 1, proof.path().to_vec());
+    assert!(!wrong_size.verify(LEAVES[5], &root));
+
+    let mut path = proof.path().to_vec();
+    path.pop();
+    let short_path = InclusionProof::new(5, proof.tree_size(), path);
+    assert!(!short_path.verify(LEAVES[5], &root));
+}
+
+#[test]
+fn consistency_proofs() {
+    let leaves: Vec<String> = (0..37).map(|index| format!("leaf {index}")).collect();
+    let tree = MerkleTree::from_leaves(&leaves);
+    for new_size in 1..=leaves.len() {
+        let new_root = tree.root_at(new_size).unwrap();
+        let new_tree = MerkleTree::from_leaves(&leaves[..new_size]);
+        for old_size in 1..=new_size {
+            let old_root = tree.root_at(old_size).unwrap();
+            let proof = new_tree.consistency_proof(old_size).unwrap();
+            assert_eq!(proof.old_size(), old_size);
+            assert_eq!(proof.new_size(), new_size);
+            assert!(proof.verify(&old_root, &new_root), "old {old_size}, new {new_size}");
+            if old_size < new_size {
+                assert!(!proof.verify(&new_root, &new_root));
+            }
+        }
+        assert_eq!(new_tree.consistency_proof(0), None);
+        assert_eq!(new_tree.consistency_proof(new_size + 1), None);
+    }
+}
+
+#[test]
+fn consistency_proof_tampered() {
+    let tree = MerkleTree::from_leaves(LEAVES);
+    let old_root = tree.root_at(3).unwrap();
+    let new_root = tree.root();
+    let proof = tree.consistency_proof(3).unwrap();
+    assert!(proof.verify(&old_root, &new_root));
+
+    let wrong_size = ConsistencyProof::new(4, proof.new_size(), proof.path().to_vec());
+    assert!(!wrong_size.verify(&old_root, &new_root));
+
+    let mut path = proof.path().to_vec();
+    path[0] = merkle::leaf_hash("tampered");
+    let tampered = ConsistencyProof::new(3, proof.new_size(), path);
+    assert!(!tampered.verify(&old_root, &new_root));
+}
+
+#[test]
+fn chunks() -> Result<()> {
+    let data: Vec<u8> = (0..=u8::MAX).cycle().take(1000).collect();
+
+    let tree = MerkleTree::from_chunks(&data, 128)?;
+    let expected = MerkleTree::from_leaves(data.chunks(128));
+    assert_eq!(tree.len(), 8);
+    assert_eq!(tree, expected);
+
+    let tree = MerkleTree::from_chunks(&data[..256], 128)?;
+    assert_eq!(tree.len(), 2);
+
+    let tree = MerkleTree::from_chunks(b"", 128)?;
+    assert!(tree.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn leaf_and_node_hash() {
+    let left = merkle::leaf_hash(LEAVES[0]);
+    let right = merkle::leaf_hash(LEAVES[1]);
+    let root: Digest = merkle::node_hash(&left, &right);
+    assert_eq!(root.to_hex_lowercase(), ROOTS[1]);
+}
@@ -0,0 +1,115 @@
+use std::io::Error as IoError;
+#[cfg(all(feature = "mmap", target_os = "linux"))]
+use std::path::Path;
+
+use assert_fs::fixture::FixtureError;
+#[cfg(feature = "mmap")]
+use assert_fs::prelude::{FileTouch, FileWriteBin, PathChild};
+#[cfg(feature = "mmap")]
+use assert_fs::TempDir;
+use chksum_sha2_256::Error as ChksumError;
+#[cfg(feature = "mmap")]
+use chksum_sha2_256::{chksum, chksum_mmap};
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Chksum(#[from] ChksumError),
+    #[error(transparent)]
+    Fixture(#[from] FixtureError),
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+#[cfg_attr(not(feature = "mmap"), ignore)]
+#[test]
+fn mmap_empty_file() -> Result<(), Error> {
+    #[cfg(feature = "mmap")]
+    {
+        let temp_dir = {
+            let temp_dir = TempDir::new()?;
+            temp_dir.child("file.txt").touch()?;
+            temp_dir
+        };
+
+        let file = temp_dir.child("file.txt");
+        let digest = chksum_mmap(file.path())?.to_hex_lowercase();
+        assert_eq!(
+            digest,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "mmap"), ignore)]
+#[test]
+fn mmap_non_empty_file() -> Result<(), Error> {
+    #[cfg(feature = "mmap")]
+    {
+        let temp_dir = {
+            let temp_dir = TempDir::new()?;
+            let file = temp_dir.child("file.txt");
+            file.touch()?;
+            file.write_binary(b"data")?;
+            temp_dir.child("large.bin").write_binary(&[0x5A; 100_000])?;
+            temp_dir
+        };
+
+        let file = temp_dir.child("file.txt");
+        let digest = chksum_mmap(file.path())?.to_hex_lowercase();
+        assert_eq!(
+            digest,
+            "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7"
+        );
+
+        let file = temp_dir.child("large.bin");
+        let digest = chksum_mmap(file.path())?.to_hex_lowercase();
+        assert_eq!(digest, chksum(file.path())?.to_hex_lowercase());
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(all(feature = "mmap", unix)), ignore)]
+#[test]
+fn mmap_special_file() -> Result<(), Error> {
+    #[cfg(all(feature = "mmap", unix))]
+    {
+        let digest = chksum_mmap("/dev/null")?.to_hex_lowercase();
+        assert_eq!(
+            digest,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(all(feature = "mmap", target_os = "linux")), ignore)]
+#[test]
+fn mmap_unmappable_file() -> Result<(), Error> {
+    #[cfg(all(feature = "mmap", target_os = "linux"))]
+    {
+        // sysfs attributes report a length but cannot be mapped
+        let path = Path::new("/sys/kernel/mm/transparent_hugepage/enabled");
+        assert_eq!(chksum_mmap(path)?, chksum(path)?);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "mmap"), ignore)]
+#[test]
+fn mmap_missing_file() -> Result<(), Error> {
+    #[cfg(feature = "mmap")]
+    {
+        let temp_dir = TempDir::new()?;
+
+        let file = temp_dir.child("missing.txt");
+        assert!(chksum_mmap(file.path()).is_err());
+    }
+
+    Ok(())
+}
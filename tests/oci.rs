@@ -0,0 +1,155 @@
+use std::io::Error as IoError;
+#[cfg(feature = "reader")]
+use std::io::{ErrorKind, Read};
+
+use assert_fs::fixture::FixtureError;
+use assert_fs::prelude::{FileWriteBin, PathChild};
+use assert_fs::TempDir;
+#[cfg(feature = "reader")]
+use chksum_sha2_256::oci::BlobReader;
+use chksum_sha2_256::oci::{self, Descriptor, ImageLayout, OciDigest, ParseError};
+use chksum_sha2_256::{Digest, Error as ChksumError, SHA2_256};
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Chksum(#[from] ChksumError),
+    #[error(transparent)]
+    Fixture(#[from] FixtureError),
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+const DATA_DIGEST: &str = "sha256:3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7";
+
+#[test]
+fn digest_parse() {
+    let digest: OciDigest = DATA_DIGEST.parse().unwrap();
+    assert_eq!(digest.algorithm(), "sha256");
+    assert_eq!(digest.to_string(), DATA_DIGEST);
+    assert_eq!(digest.digest(), Some(SHA2_256::hash(b"data")));
+    assert_eq!(OciDigest::from(SHA2_256::hash(b"data")), digest);
+
+    let digest: OciDigest = "multihash+base58:QmRZxt2b1FVZPNqd8hsiykDL3TdBDeTSPX9Kv46HmX4Gx8"
+        .parse()
+        .unwrap();
+    assert_eq!(digest.algorithm(), "multihash+base58");
+    assert_eq!(digest.digest(), None);
+
+    assert_eq!("sha256".parse::<OciDigest>(), Err(ParseError::MissingSeparator));
+    assert_eq!(":abc".parse::<OciDigest>(), Err(ParseError::InvalidAlgorithm));
+    assert_eq!("SHA256:abc".parse::<OciDigest>(), Err(ParseError::InvalidAlgorithm));
+    assert_eq!("sha256+:abc".parse::<OciDigest>(), Err(ParseError::InvalidAlgorithm));
+    assert_eq!("sha512:".parse::<OciDigest>(), Err(ParseError::InvalidEncoded));
+    assert_eq!("sha512:a/b".parse::<OciDigest>(), Err(ParseError::InvalidEncoded));
+    assert_eq!("sha256:abc".parse::<OciDigest>(), Err(ParseError::InvalidEncoded));
+    assert_eq!(
+        DATA_DIGEST
+            .to_uppercase()
+            .replace("SHA256", "sha256")
+            .parse::<OciDigest>(),
+        Err(ParseError::InvalidEncoded)
+    );
+}
+
+#[test]
+fn digest_chksum() -> Result<(), Error> {
+    assert_eq!(oci::chksum(b"data")?.to_string(), DATA_DIGEST);
+
+    let descriptor = Descriptor::from_blob("application/octet-stream", b"data");
+    assert_eq!(descriptor.media_type(), "application/octet-stream");
+    assert_eq!(descriptor.digest().to_string(), DATA_DIGEST);
+    assert_eq!(descriptor.size(), 4);
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "reader"), ignore)]
+#[test]
+fn blob_reader() -> Result<(), Error> {
+    #[cfg(feature = "reader")]
+    {
+        let descriptor = Descriptor::from_blob("application/octet-stream", b"data");
+
+        let mut reader = BlobReader::new(&b"data"[..], &descriptor)?;
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer)?;
+        assert_eq!(buffer, b"data");
+        assert_eq!(reader.bytes_read(), 4);
+        oci::verify(&b"data"[..], &descriptor)?;
+
+        let mut reader = BlobReader::new(&b"date"[..], &descriptor)?;
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains("digest mismatch"));
+
+        let mut reader = BlobReader::new(&b"dat"[..], &descriptor)?;
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert!(error.to_string().contains("size mismatch"));
+
+        let mut reader = BlobReader::new(&b"data and more"[..], &descriptor)?;
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert!(error.to_string().contains("size mismatch"));
+
+        let digest = "sha512:abc".parse().unwrap();
+        let descriptor = Descriptor::new("application/octet-stream", digest, 4);
+        let error = BlobReader::new(&b"data"[..], &descriptor).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn image_layout() -> Result<(), Error> {
+    let temp_dir = TempDir::new()?;
+    assert!(ImageLayout::open(temp_dir.path()).is_err());
+
+    let layout = ImageLayout::init(temp_dir.path())?;
+    assert!(temp_dir.child("oci-layout").path().is_file());
+    assert!(temp_dir.child("index.json").path().is_file());
+    let layout = ImageLayout::open(layout.root())?;
+
+    let digest = layout.write_blob(b"data")?;
+    assert_eq!(digest.to_string(), DATA_DIGEST);
+    assert_eq!(std::fs::read(layout.blob_path(&digest))?, b"data");
+    assert_eq!(layout.write_blob(b"data")?, digest);
+    assert!(layout.verify()?.is_empty());
+    assert_eq!(std::fs::read_dir(temp_dir.child("blobs/sha256").path())?.count(), 1);
+
+    // leftovers of interrupted writes are not reported as corrupted blobs
+    temp_dir
+        .child("blobs/sha256")
+        .child(format!(".{}.1.1.tmp", digest.encoded()))
+        .write_binary(b"dat")?;
+    assert!(layout.verify()?.is_empty());
+
+    let descriptor = Descriptor::from_blob("application/octet-stream", b"data");
+    layout.verify_blob(&descriptor)?;
+
+    let other = layout.write_blob(b"other")?;
+    temp_dir
+        .child("blobs/sha256")
+        .child(other.encoded())
+        .write_binary(b"tampered")?;
+    temp_dir.child("blobs/sha256/garbage").write_binary(b"")?;
+    let corrupted = layout.verify()?;
+    assert_eq!(corrupted.len(), 2);
+    assert!(corrupted.contains(&other));
+    assert!(corrupted.iter().any(|digest| digest.encoded() == "garbage"));
+
+    let descriptor = Descriptor::new("application/octet-stream", other, 5);
+    assert!(layout.verify_blob(&descriptor).is_err());
+
+    let descriptor = Descriptor::new("application/octet-stream", SHA2_256::hash(b"missing").into(), 7);
+    assert!(layout.verify_blob(&descriptor).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn digest_try_from_str() {
+    let digest = Digest::try_from("3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7").unwrap();
+    assert_eq!(digest, SHA2_256::hash(b"data"));
+    assert!(Digest::try_from("3a6e").is_err());
+}
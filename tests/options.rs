@@ -0,0 +1,275 @@
+use std::fs::{read_dir, File};
+use std::io::Error as IoError;
+
+use assert_fs::fixture::FixtureError;
+use assert_fs::prelude::{FileTouch, FileWriteBin, PathChild};
+use assert_fs::TempDir;
+#[cfg(feature = "async-runtime-tokio")]
+use chksum_sha2_256::async_chksum_with;
+use chksum_sha2_256::{chksum, chksum_with, ChksumOptions, Error as ChksumError};
+#[cfg(feature = "async-runtime-tokio")]
+use tokio::fs::File as TokioFile;
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Chksum(#[from] ChksumError),
+    #[error(transparent)]
+    Fixture(#[from] FixtureError),
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+#[test]
+fn buffer_size() {
+    let options = ChksumOptions::new();
+    assert_eq!(options.buffer_size(), 8 * 1024);
+
+    let options = ChksumOptions::with_buffer_size(3);
+    assert_eq!(options.buffer_size(), 3);
+
+    let options = ChksumOptions::with_buffer(Vec::with_capacity(16));
+    assert_eq!(options.buffer_size(), 16);
+
+    let options = ChksumOptions::with_buffer(vec![0; 32]);
+    assert_eq!(options.buffer_size(), 32);
+}
+
+#[test]
+#[should_panic(expected = "buffer size must be greater than zero")]
+fn zero_buffer_size() {
+    let _ = ChksumOptions::with_buffer_size(0);
+}
+
+#[test]
+fn bytes() -> Result<(), Error> {
+    let mut options = ChksumOptions::new();
+
+    let digest = chksum_with(b"example data", &mut options)?.to_hex_lowercase();
+    assert_eq!(
+        digest,
+        "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn file_with_small_buffer() -> Result<(), Error> {
+    let temp_dir = {
+        let temp_dir = TempDir::new()?;
+        let file = temp_dir.child("file.txt");
+        file.touch()?;
+        file.write_binary(&[0xAB; 1000])?;
+        temp_dir
+    };
+
+    let path = temp_dir.child("file.txt");
+    let expected = chksum(path.path())?.to_hex_lowercase();
+
+    for buffer_size in [1, 7, 64, 1000, 4096] {
+        let mut options = ChksumOptions::with_buffer_size(buffer_size);
+        let file = File::open(path.path())?;
+        let digest = chksum_with(file, &mut options)?.to_hex_lowercase();
+        assert_eq!(digest, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn reused_buffer() -> Result<(), Error> {
+    let temp_dir = {
+        let temp_dir = TempDir::new()?;
+        for (name, data) in [("a.txt", &b"a"[..]), ("b.txt", b"bb"), ("c.txt", b"")] {
+            let file = temp_dir.child(name);
+            file.touch()?;
+            file.write_binary(data)?;
+        }
+        temp_dir
+    };
+
+    let mut options = ChksumOptions::with_buffer(Vec::with_capacity(2));
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        let path = temp_dir.child(name);
+        let digest = chksum_with(path.path(), &mut options)?;
+        assert_eq!(digest.to_hex_lowercase(), chksum(path.path())?.to_hex_lowercase());
+    }
+
+    let buffer = options.into_buffer();
+    assert_eq!(buffer.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn directory() -> Result<(), Error> {
+    let temp_dir = {
+        let temp_dir = TempDir::new()?;
+        temp_dir.child("file.txt").write_binary(b"data")?;
+        temp_dir.child("nested/file.txt").write_binary(b"nested data")?;
+        temp_dir
+    };
+
+    let mut options = ChksumOptions::with_buffer_size(3);
+    let digest = chksum_with(read_dir(temp_dir.path())?, &mut options)?.to_hex_lowercase();
+    assert_eq!(digest, chksum(temp_dir.path())?.to_hex_lowercase());
+
+    let digest = chksum_with(temp_dir.to_path_buf(), &mut options)?.to_hex_lowercase();
+    assert_eq!(digest, chksum(temp_dir.path())?.to_hex_lowercase());
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "async-runtime-tokio"), ignore)]
+#[tokio::test]
+async fn async_runtime_tokio_file_with_small_buffer() -> Result<(), Error> {
+    #[cfg(feature = "async-runtime-tokio")]
+    {
+        let temp_dir = {
+            let temp_dir = TempDir::new()?;
+            temp_dir.child("file.txt").write_binary(&[0xAB; 1000])?;
+            temp_dir.child("nested/file.txt").write_binary(b"nested data")?;
+            temp_dir
+        };
+
+        let path = temp_dir.child("file.txt");
+        let mut options = ChksumOptions::with_buffer_size(7);
+        let file = TokioFile::open(path.path()).await?;
+        let digest = async_chksum_with(file, &mut options).await?.to_hex_lowercase();
+        assert_eq!(digest, chksum(path.path())?.to_hex_lowercase());
+
+        let digest = async_chksum_with(temp_dir.path(), &mut options)
+            .await?
+            .to_hex_lowercase();
+        assert_eq!(digest, chksum(temp_dir.path())?.to_hex_lowercase());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn directory_without_metadata() -> Result<(), Error> {
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("file").write_binary(b"data")?;
+
+    let mut options = ChksumOptions::new()
+        .include_permissions(false)
+        .include_executable(false)
+        .include_symlink_targets(false);
+    assert!(!options.includes_permissions());
+    assert!(!options.includes_executable());
+    assert!(!options.includes_symlink_targets());
+    assert_eq!(chksum_with(temp_dir.path(), &mut options)?, chksum(temp_dir.path())?);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn directory_with_metadata_encoding() -> Result<(), Error> {
+    use std::fs::{set_permissions, Permissions};
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    use chksum_sha2_256::SHA2_256;
+
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("file").write_binary(b"data")?;
+    set_permissions(temp_dir.child("file"), Permissions::from_mode(0o755))?;
+    symlink("file", temp_dir.child("link"))?;
+
+    let mut options = ChksumOptions::new()
+        .include_permissions(true)
+        .include_executable(true)
+        .include_symlink_targets(true);
+    let link_mode = temp_dir.child("link").symlink_metadata()?.permissions().mode() & 0o7777;
+
+    let mut hash = SHA2_256::new();
+    hash.update(b"f\x01\xed\x01");
+    hash.update(4u64.to_be_bytes());
+    hash.update(b"data");
+    hash.update(b"l");
+    hash.update((link_mode as u16).to_be_bytes());
+    hash.update(b"\x00");
+    hash.update(4u64.to_be_bytes());
+    hash.update(b"file");
+    assert_eq!(chksum_with(temp_dir.path(), &mut options)?, hash.digest());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn directory_with_metadata() -> Result<(), Error> {
+    use std::fs::{set_permissions, Permissions};
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let temp_dir = TempDir::new()?;
+    temp_dir.child("script").write_binary(b"data")?;
+    temp_dir.child("other").write_binary(b"data")?;
+    symlink("script", temp_dir.child("link"))?;
+    let script = temp_dir.child("script");
+    set_permissions(&script, Permissions::from_mode(0o644))?;
+
+    let digests = |options: &mut ChksumOptions| chksum_with(temp_dir.path(), options);
+    let mut content = ChksumOptions::new();
+    let mut executable = ChksumOptions::new().include_executable(true);
+    let mut permissions = ChksumOptions::new().include_permissions(true);
+    let mut symlinks = ChksumOptions::new().include_symlink_targets(true);
+    let before = [
+        digests(&mut content)?,
+        digests(&mut executable)?,
+        digests(&mut permissions)?,
+        digests(&mut symlinks)?,
+    ];
+
+    // `chmod +x` changes the digest only when the executable flag or permissions are included
+    set_permissions(&script, Permissions::from_mode(0o755))?;
+    assert_eq!(digests(&mut content)?, before[0]);
+    assert_ne!(digests(&mut executable)?, before[1]);
+    assert_ne!(digests(&mut permissions)?, before[2]);
+    assert_eq!(digests(&mut symlinks)?, before[3]);
+
+    // group write permission isn't the executable flag
+    set_permissions(&script, Permissions::from_mode(0o664))?;
+    assert_eq!(digests(&mut executable)?, before[1]);
+    assert_ne!(digests(&mut permissions)?, before[2]);
+    set_permissions(&script, Permissions::from_mode(0o644))?;
+
+    // retargeting a link to a file with the same content is only visible with symlink targets
+    std::fs::remove_file(temp_dir.child("link"))?;
+    symlink("other", temp_dir.child("link"))?;
+    assert_eq!(digests(&mut content)?, before[0]);
+    assert_eq!(digests(&mut executable)?, before[1]);
+    assert_ne!(digests(&mut symlinks)?, before[3]);
+
+    // single files never include metadata
+    let mut options = ChksumOptions::new().include_permissions(true).include_executable(true);
+    assert_eq!(chksum_with(script.path(), &mut options)?, chksum(script.path())?);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[cfg_attr(not(feature = "async-runtime-tokio"), ignore)]
+#[tokio::test]
+async fn async_runtime_tokio_directory_with_metadata() -> Result<(), Error> {
+    #[cfg(feature = "async-runtime-tokio")]
+    {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new()?;
+        temp_dir.child("file").write_binary(b"data")?;
+        temp_dir.child("dir").child("nested").write_binary(b"example data")?;
+        symlink("file", temp_dir.child("link"))?;
+
+        let mut options = ChksumOptions::new()
+            .include_permissions(true)
+            .include_executable(true)
+            .include_symlink_targets(true);
+        let digest = async_chksum_with(temp_dir.path(), &mut options).await?;
+        assert_eq!(digest, chksum_with(temp_dir.path(), &mut options)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io::{Cursor, ErrorKind};
+
+use assert_fs::prelude::{FileWriteBin, PathChild};
+use assert_fs::TempDir;
+#[cfg(feature = "async-runtime-tokio")]
+use chksum_sha2_256::async_chksum_range;
+use chksum_sha2_256::{chksum_range, Error, SHA2_256};
+
+type BoxError = Box<dyn std::error::Error>;
+
+const DIGEST: &str = "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061";
+
+fn is_short(error: &Error) -> bool {
+    matches!(error, Error::Io(error) if error.kind() == ErrorKind::UnexpectedEof)
+}
+
+#[test]
+fn range_data() -> Result<(), BoxError> {
+    let data = b"some example data, and more";
+
+    let digest = chksum_range(Cursor::new(data), 5, 12)?;
+    assert_eq!(digest.to_hex_lowercase(), DIGEST);
+
+    let digest = chksum_range(Cursor::new(data), 0, data.len() as u64)?;
+    assert_eq!(digest, SHA2_256::hash(data));
+
+    let digest = chksum_range(Cursor::new(data), 27, 0)?;
+    assert_eq!(digest, SHA2_256::hash(b""));
+    Ok(())
+}
+
+#[test]
+fn range_file() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.child("disk.img");
+    let mut data = vec![0xA5; 100_000];
+    data[70_000..70_012].copy_from_slice(b"example data");
+    file.write_binary(&data)?;
+
+    let digest = chksum_range(File::open(file.path())?, 70_000, 12)?;
+    assert_eq!(digest.to_hex_lowercase(), DIGEST);
+
+    let mut handle = File::open(file.path())?;
+    let digest = chksum_range(&mut handle, 1_000, 90_000)?;
+    assert_eq!(digest, SHA2_256::hash(&data[1_000..91_000]));
+
+    // the file is repositioned for each range
+    let digest = chksum_range(&mut handle, 70_000, 12)?;
+    assert_eq!(digest.to_hex_lowercase(), DIGEST);
+    Ok(())
+}
+
+#[test]
+fn range_shorter_file() {
+    let data = b"example data";
+
+    let error = chksum_range(Cursor::new(data), 5, 12).unwrap_err();
+    assert!(is_short(&error));
+    assert_eq!(
+        error.to_string(),
+        "file is shorter than the requested range `5..17`, it ends at `12`"
+    );
+
+    let error = chksum_range(Cursor::new(data), 20, 1).unwrap_err();
+    assert!(is_short(&error));
+}
+
+#[cfg_attr(not(feature = "async-runtime-tokio"), ignore)]
+#[tokio::test]
+async fn async_runtime_tokio_range_file() -> Result<(), BoxError> {
+    #[cfg(feature = "async-runtime-tokio")]
+    {
+        let temp_dir = TempDir::new()?;
+        let file = temp_dir.child("disk.img");
+        let mut data = vec![0xA5; 100_000];
+        data[70_000..70_012].copy_from_slice(b"example data");
+        file.write_binary(&data)?;
+
+        let handle = tokio::fs::File::open(file.path()).await?;
+        let digest = async_chksum_range(handle, 70_000, 12).await?;
+        assert_eq!(digest.to_hex_lowercase(), DIGEST);
+
+        let digest = async_chksum_range(Cursor::new(&data), 1_000, 90_000).await?;
+        assert_eq!(digest, SHA2_256::hash(&data[1_000..91_000]));
+
+        let error = async_chksum_range(Cursor::new(&data), 99_990, 12).await.unwrap_err();
+        assert!(is_short(&error));
+    }
+    Ok(())
+}
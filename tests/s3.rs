@@ -0,0 +1,124 @@
+use std::fs::File;
+use std::io::ErrorKind;
+
+use assert_fs::prelude::{FileWriteBin, PathChild};
+use assert_fs::TempDir;
+use chksum_sha2_256::s3::{self, CompositeChecksum, ParseError};
+use chksum_sha2_256::{Error, SHA2_256};
+
+type BoxError = Box<dyn std::error::Error>;
+
+const CHECKSUM: &str = "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-2";
+
+fn is_mismatch(error: &Error) -> bool {
+    matches!(error, Error::Io(error) if error.kind() == ErrorKind::InvalidData)
+}
+
+#[test]
+fn s3_chksum() -> Result<(), BoxError> {
+    let checksum = s3::chksum(&b"example data"[..], 8)?;
+    assert_eq!(checksum.part_size(), 8);
+    assert_eq!(checksum.parts(), [SHA2_256::hash(b"example "), SHA2_256::hash(b"data")]);
+    assert_eq!(checksum.to_string(), CHECKSUM);
+
+    let composite = checksum.composite();
+    assert_eq!(composite.part_count(), 2);
+    let mut hash = SHA2_256::new();
+    hash.update(SHA2_256::hash(b"example "));
+    hash.update(SHA2_256::hash(b"data"));
+    assert_eq!(composite.digest(), hash.digest());
+    assert_eq!(CompositeChecksum::from_parts(checksum.parts()), composite);
+    Ok(())
+}
+
+#[test]
+fn s3_chksum_part_boundaries() -> Result<(), BoxError> {
+    // a part size dividing the length doesn't add an empty part
+    let checksum = s3::chksum(&b"example data"[..], 6)?;
+    assert_eq!(checksum.parts(), [SHA2_256::hash(b"exampl"), SHA2_256::hash(b"e data")]);
+
+    let checksum = s3::chksum(&b"example data"[..], 12)?;
+    assert_eq!(checksum.parts(), [SHA2_256::hash(b"example data")]);
+    assert!(checksum.to_string().ends_with("-1"));
+
+    let checksum = s3::chksum(&b""[..], 8)?;
+    assert_eq!(checksum.parts(), [SHA2_256::hash(b"")]);
+    assert_eq!(checksum.to_string(), "Xfbg4nYTWdMKgnUFjimfzAOBU0VF9Vz0PkGYP11MlFY=-1");
+    Ok(())
+}
+
+#[test]
+fn s3_chksum_file() -> Result<(), BoxError> {
+    let temp_dir = TempDir::new()?;
+    let data: Vec<u8> = (0..100_000u32).map(|index| (index % 251) as u8).collect();
+    temp_dir.child("object.bin").write_binary(&data)?;
+
+    let checksum = s3::chksum(File::open(temp_dir.child("object.bin").path())?, 16 * 1024)?;
+    assert_eq!(checksum.parts().len(), 7);
+    let parts: Vec<_> = data.chunks(16 * 1024).map(SHA2_256::hash).collect();
+    assert_eq!(checksum.parts(), parts);
+
+    s3::verify(
+        &checksum.to_string(),
+        File::open(temp_dir.child("object.bin").path())?,
+        16 * 1024,
+    )?;
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "part size must be greater than zero")]
+fn s3_chksum_zero_part_size() {
+    let _ = s3::chksum(&b"data"[..], 0);
+}
+
+#[test]
+fn s3_parse() {
+    let checksum: CompositeChecksum = CHECKSUM.parse().unwrap();
+    assert_eq!(checksum.part_count(), 2);
+    assert_eq!(checksum.to_string(), CHECKSUM);
+
+    let quoted: CompositeChecksum = format!("\"{CHECKSUM}\"").parse().unwrap();
+    assert_eq!(quoted, checksum);
+
+    assert_eq!(
+        "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=".parse::<CompositeChecksum>(),
+        Err(ParseError::MissingPartCount)
+    );
+    assert_eq!(
+        "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-0".parse::<CompositeChecksum>(),
+        Err(ParseError::InvalidPartCount)
+    );
+    assert_eq!(
+        "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMXg=-+2".parse::<CompositeChecksum>(),
+        Err(ParseError::InvalidPartCount)
+    );
+    assert_eq!(
+        "8c6SfCOVfUqLQ3FJPinKbpUMUu0wMofqriwV9o9GMX=-2".parse::<CompositeChecksum>(),
+        Err(ParseError::InvalidDigest)
+    );
+    assert_eq!(
+        "ZGF0YQ==-2".parse::<CompositeChecksum>(),
+        Err(ParseError::InvalidDigest)
+    );
+}
+
+#[test]
+fn s3_verify() {
+    assert!(s3::verify(CHECKSUM, &b"example data"[..], 8).is_ok());
+
+    // a different part size changes the composite checksum
+    let error = s3::verify(CHECKSUM, &b"example data"[..], 4).unwrap_err();
+    assert!(is_mismatch(&error));
+    let actual = s3::chksum(&b"example data"[..], 4).unwrap();
+    assert_eq!(
+        error.to_string(),
+        format!("checksum mismatch, expected `{CHECKSUM}`, got `{actual}`")
+    );
+
+    let error = s3::verify(CHECKSUM, &b"example date"[..], 8).unwrap_err();
+    assert!(is_mismatch(&error));
+
+    let error = s3::verify("invalid", &b"example data"[..], 8).unwrap_err();
+    assert!(is_mismatch(&error));
+}
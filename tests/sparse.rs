@@ -0,0 +1,95 @@
+#[cfg(feature = "sparse")]
+use std::fs::OpenOptions;
+#[cfg(feature = "sparse")]
+use std::io::{Seek, SeekFrom, Write};
+#[cfg(feature = "sparse")]
+use std::path::Path;
+
+#[cfg(feature = "sparse")]
+use assert_fs::prelude::{FileTouch, FileWriteBin, PathChild};
+#[cfg(feature = "sparse")]
+use assert_fs::TempDir;
+#[cfg(feature = "sparse")]
+use chksum_sha2_256::{chksum, chksum_sparse, SHA2_256};
+
+type BoxError = Box<dyn std::error::Error>;
+
+/// Creates a sparse file of the given length with data written at the given offsets.
+#[cfg(feature = "sparse")]
+fn create_sparse(path: &Path, length: u64, chunks: &[(u64, &[u8])]) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).truncate(true).write(true).open(path)?;
+    file.set_len(length)?;
+    for (offset, data) in chunks {
+        file.seek(SeekFrom::Start(*offset))?;
+        file.write_all(data)?;
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "sparse"), ignore)]
+#[test]
+fn sparse_regular_files() -> Result<(), BoxError> {
+    #[cfg(feature = "sparse")]
+    {
+        let temp_dir = TempDir::new()?;
+        temp_dir.child("empty").touch()?;
+        temp_dir.child("file.txt").write_binary(b"example data")?;
+
+        let digest = chksum_sparse(temp_dir.child("empty").path())?.to_hex_lowercase();
+        assert_eq!(
+            digest,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let digest = chksum_sparse(temp_dir.child("file.txt").path())?.to_hex_lowercase();
+        assert_eq!(
+            digest,
+            "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061"
+        );
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "sparse"), ignore)]
+#[test]
+fn sparse_holes() -> Result<(), BoxError> {
+    #[cfg(feature = "sparse")]
+    {
+        const MIB: u64 = 1024 * 1024;
+
+        let temp_dir = TempDir::new()?;
+
+        // leading, inner and trailing holes
+        let path = temp_dir.join("holes.img");
+        create_sparse(&path, 4 * MIB, &[(MIB, b"data"), (3 * MIB + 7, b"example data")])?;
+        assert_eq!(chksum_sparse(&path)?, chksum(&path)?);
+
+        // a hole only
+        let path = temp_dir.join("hole.img");
+        create_sparse(&path, MIB + 3, &[])?;
+        let digest = chksum_sparse(&path)?;
+        assert_eq!(digest, SHA2_256::hash(vec![0; 1024 * 1024 + 3]));
+        assert_eq!(digest, chksum(&path)?);
+
+        // data at both ends
+        let path = temp_dir.join("ends.img");
+        create_sparse(&path, 2 * MIB, &[(0, b"data"), (2 * MIB - 4, b"data")])?;
+        assert_eq!(chksum_sparse(&path)?, chksum(&path)?);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[cfg_attr(not(feature = "sparse"), ignore)]
+#[test]
+fn sparse_special_file() -> Result<(), BoxError> {
+    #[cfg(feature = "sparse")]
+    {
+        let digest = chksum_sparse("/dev/null")?.to_hex_lowercase();
+        assert_eq!(
+            digest,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+#[cfg(feature = "writer")]
+use std::fs;
+#[cfg(feature = "writer")]
+use std::io::{ErrorKind, Write};
+#[cfg(feature = "writer")]
+use std::path::Path;
+
+#[cfg(feature = "writer")]
+use assert_fs::TempDir;
+#[cfg(feature = "writer")]
+use chksum_sha2_256::writer::AtomicWriter;
+#[cfg(feature = "writer")]
+use chksum_sha2_256::{Digest, Error};
+
+type BoxError = Box<dyn std::error::Error>;
+
+#[cfg(feature = "writer")]
+const DIGEST: &str = "44752f37272e944fd2c913a35342eaccdd1aaf189bae50676b301ab213fc5061";
+
+#[cfg(feature = "writer")]
+fn entries(dir: &Path) -> Result<Vec<String>, BoxError> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<_>, BoxError>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+#[cfg_attr(not(feature = "writer"), ignore)]
+#[test]
+fn atomic_writer_commit() -> Result<(), BoxError> {
+    #[cfg(feature = "writer")]
+    {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.join("artifact");
+        fs::write(&path, b"previous data")?;
+
+        let mut writer = AtomicWriter::create(&path)?;
+        assert_eq!(writer.path(), path);
+        writer.write_all(b"example ")?;
+        writer.write_all(b"data")?;
+        assert_eq!(fs::read(&path)?, b"previous data");
+        assert_eq!(entries(&temp_dir)?.len(), 2);
+
+        let digest = writer.commit()?;
+        assert_eq!(digest.to_hex_lowercase(), DIGEST);
+        assert_eq!(fs::read(&path)?, b"example data");
+        assert_eq!(entries(&temp_dir)?, ["artifact"]);
+
+        let expected = Digest::try_from(DIGEST)?;
+        let path = temp_dir.join("verified");
+        let mut writer = AtomicWriter::create(&path)?.with_expected(expected);
+        writer.write_all(b"example data")?;
+        assert_eq!(writer.commit()?, expected);
+        assert_eq!(fs::read(&path)?, b"example data");
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "writer"), ignore)]
+#[test]
+fn atomic_writer_discard() -> Result<(), BoxError> {
+    #[cfg(feature = "writer")]
+    {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.join("artifact");
+
+        let expected = Digest::try_from(DIGEST)?;
+        let mut writer = AtomicWriter::create(&path)?.with_expected(expected);
+        writer.write_all(b"tampered data")?;
+        let error = writer.commit().unwrap_err();
+        assert!(matches!(error, Error::Io(error) if error.kind() == ErrorKind::InvalidData));
+        assert!(entries(&temp_dir)?.is_empty());
+
+        let mut writer = AtomicWriter::create(&path)?;
+        writer.write_all(b"example data")?;
+        writer.abort()?;
+        assert!(entries(&temp_dir)?.is_empty());
+
+        let mut writer = AtomicWriter::create(&path)?;
+        writer.write_all(b"example data")?;
+        drop(writer);
+        assert!(entries(&temp_dir)?.is_empty());
+
+        assert!(AtomicWriter::create(temp_dir.join("missing").join("artifact")).is_err());
+    }
+
+    Ok(())
+}